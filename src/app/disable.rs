@@ -0,0 +1,119 @@
+use super::journal::{JournalEntry, Operation, OperationKind};
+use super::state::PluginManager;
+use crate::plugin::{find_duplicates, find_redundant_formats, Plugin};
+use crate::scanner::disabled_directory;
+use crate::utils::error::Result;
+use anyhow::{bail, Context};
+use std::path::PathBuf;
+
+impl PluginManager {
+    /// Moves a plugin into its format folder's "(Disabled)" sibling so DAWs stop
+    /// loading it, keeping its place below the format folder.
+    pub fn disable_plugin(&mut self, plugin: &Plugin) {
+        self.set_disabled_reporting(plugin, true);
+    }
+
+    /// Moves a disabled plugin back to where DAWs look for it.
+    pub fn enable_plugin(&mut self, plugin: &Plugin) {
+        self.set_disabled_reporting(plugin, false);
+    }
+
+    fn set_disabled_reporting(&mut self, plugin: &Plugin, disabled: bool) {
+        let errors = self.set_disabled(&[(plugin.clone(), disabled)]);
+        if !errors.is_empty() {
+            self.move_error = Some(errors.join("\n"));
+        }
+    }
+
+    fn disabled_path(&self, plugin: &Plugin) -> Result<PathBuf> {
+        let root = self
+            .scanner
+            .plugin_directories()
+            .into_iter()
+            .filter(|dir| plugin.path.starts_with(dir))
+            .max_by_key(|dir| dir.components().count())
+            .context("The plugin isn't in a standard plugin folder")?;
+        let relative = plugin.path.strip_prefix(&root)?;
+        Ok(disabled_directory(&root).join(relative))
+    }
+
+    fn enabled_path(&self, plugin: &Plugin) -> Result<PathBuf> {
+        let root = self
+            .scanner
+            .plugin_directories()
+            .into_iter()
+            .filter(|dir| plugin.path.starts_with(disabled_directory(dir)))
+            .max_by_key(|dir| dir.components().count())
+            .context("The plugin isn't in a disabled plugin folder")?;
+        let relative = plugin.path.strip_prefix(disabled_directory(&root))?;
+        Ok(root.join(relative))
+    }
+
+    fn move_for_state(&self, plugin: &Plugin, disabled: bool) -> Result<PathBuf> {
+        if self.is_protected(plugin) {
+            bail!("It is protected; unlock it first");
+        }
+        let destination = if disabled {
+            self.disabled_path(plugin)?
+        } else {
+            self.enabled_path(plugin)?
+        };
+        if destination.exists() {
+            bail!("{:?} already exists", destination);
+        }
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::rename(&plugin.path, &destination)?;
+        Ok(destination)
+    }
+
+    /// Disables or enables each plugin, journals the moves as one operation so they're
+    /// undone together, and updates the plugins in place instead of rescanning.
+    /// Returns a message for each plugin that couldn't be moved.
+    pub(super) fn set_disabled(&mut self, changes: &[(Plugin, bool)]) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut entries = Vec::new();
+
+        for (plugin, disabled) in changes {
+            let destination = match self.move_for_state(plugin, *disabled) {
+                Ok(destination) => destination,
+                Err(e) => {
+                    let action = if *disabled { "disable" } else { "enable" };
+                    errors.push(format!("Could not {} {}: {}", action, plugin.name, e));
+                    continue;
+                }
+            };
+
+            entries.push(JournalEntry {
+                original: plugin.path.clone(),
+                moved_to: Some(destination.clone()),
+                size: plugin.size,
+                plugin: Some(plugin.clone()),
+            });
+            self.selected_plugins.remove(&plugin.path);
+            if let Some(listed) = self
+                .plugins
+                .values_mut()
+                .flatten()
+                .find(|p| p.path == plugin.path)
+            {
+                listed.path = destination;
+                listed.disabled = *disabled;
+            }
+        }
+
+        self.record_operation(Operation::new(OperationKind::Move, entries));
+        self.refresh_manufacturer_selection();
+        self.refresh_project_usage();
+        self.refresh_version_mismatches();
+        if !self.duplicates.is_empty() || self.show_duplicates {
+            self.duplicates = find_duplicates(self.plugins.values().flatten());
+        }
+        if !self.redundant_formats.is_empty() || self.show_format_cleanup {
+            self.redundant_formats =
+                find_redundant_formats(self.plugins.values().flatten(), &self.format_policy);
+        }
+        errors
+    }
+}
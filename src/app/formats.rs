@@ -0,0 +1,33 @@
+use super::state::PluginManager;
+use crate::plugin::{find_redundant_formats, PluginType};
+
+impl PluginManager {
+    pub fn find_redundant_formats(&mut self) {
+        self.redundant_formats =
+            find_redundant_formats(self.plugins.values().flatten(), &self.format_policy);
+        self.show_format_cleanup = true;
+    }
+
+    /// Adds or removes a format from the preferred policy and recomputes the proposal.
+    pub fn toggle_policy_format(&mut self, format: PluginType) {
+        if !self.format_policy.keep.remove(&format) {
+            self.format_policy.keep.insert(format);
+        }
+        if let Err(e) = self.format_policy.save() {
+            eprintln!("Error saving format policy: {}", e);
+        }
+        self.find_redundant_formats();
+    }
+
+    /// Selects every proposed copy that isn't protected, for the usual confirmation.
+    pub fn select_redundant_formats(&mut self) {
+        let redundant: Vec<_> = self
+            .redundant_formats
+            .iter()
+            .filter(|r| !self.is_protected(&r.plugin))
+            .map(|r| r.plugin.path.clone())
+            .collect();
+        self.selected_plugins.extend(redundant);
+        self.refresh_manufacturer_selection();
+    }
+}
@@ -1,9 +1,14 @@
-use super::state::PluginManager;
-use crate::plugin::Plugin;
+use super::plan::ItemKind;
+use super::state::{PluginManager, SortOrder};
+use crate::plugin::{Plugin, PluginType, RULE_HELP};
+use crate::utils::fs::{format_size, TrashOutcome};
 use eframe::egui;
 
 impl eframe::App for PluginManager {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.poll_update_check(ctx);
+        self.poll_project_scan(ctx);
+
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("Plugin Manager");
 
@@ -17,14 +22,147 @@ impl eframe::App for PluginManager {
                     ui.label("Scanning...");
                 }
 
-                if !self.selected_plugins.is_empty() {
+                if !self.plugins.is_empty() && ui.button("Find Duplicates").clicked() {
+                    self.find_duplicate_plugins();
+                }
+
+                if !self.plugins.is_empty()
+                    && ui
+                        .button("Clean Up Formats...")
+                        .on_hover_text("Find copies of products in formats you don't use")
+                        .clicked()
+                {
+                    self.find_redundant_formats();
+                }
+
+                let projects_button = ui.add_enabled(
+                    self.project_scan.is_none(),
+                    egui::Button::new("Scan Projects..."),
+                );
+                let projects_button = match (&self.project_scan, &self.project_folder) {
+                    (Some((folder, _)), _) => projects_button.on_disabled_hover_text(format!(
+                        "Reading projects in {}",
+                        folder.display()
+                    )),
+                    (None, Some(folder)) => projects_button.on_hover_text(format!(
+                        "{} projects in {}",
+                        self.projects.len(),
+                        folder.display()
+                    )),
+                    (None, None) => projects_button
+                        .on_hover_text("Find which plugins your Reaper and Ableton projects use"),
+                };
+                if projects_button.clicked() {
+                    self.choose_project_folder();
+                }
+                if self.project_scan.is_some() {
+                    ui.spinner();
+                }
+
+                let flagged_count = self
+                    .plugins
+                    .values()
+                    .flatten()
+                    .filter(|p| !self.daw_flags(p).is_empty())
+                    .count();
+                if flagged_count > 0
+                    && ui
+                        .button(format!("Select Broken ({})", flagged_count))
+                        .on_hover_text("Select plugins a DAW failed to load or has blocklisted")
+                        .clicked()
+                {
+                    self.select_flagged_plugins();
+                }
+
+                egui::ComboBox::from_id_source("sort_order")
+                    .selected_text(format!("Sort: {}", self.sort_order.label()))
+                    .show_ui(ui, |ui| {
+                        for order in SortOrder::ALL {
+                            ui.selectable_value(&mut self.sort_order, order, order.label());
+                        }
+                    });
+
+                let active = self.profiles.active.clone();
+                let profile_names: Vec<String> = self.profiles.profiles.keys().cloned().collect();
+                egui::ComboBox::from_id_source("profile")
+                    .selected_text(format!("Profile: {}", active.as_deref().unwrap_or("None")))
+                    .show_ui(ui, |ui| {
+                        if profile_names.is_empty() {
+                            ui.weak("Select plugins, then save them as a profile");
+                        }
+                        for name in &profile_names {
+                            if ui
+                                .selectable_label(active.as_ref() == Some(name), name)
+                                .clicked()
+                            {
+                                self.preview_profile_switch(name);
+                            }
+                        }
+                    });
+
+                if ui.button("Select by Rule...").clicked() {
+                    self.show_rule_window = true;
+                }
+                if ui.button("Invert Selection").clicked() {
+                    self.invert_selection();
+                }
+
+                let update_count = self
+                    .plugins
+                    .values()
+                    .flatten()
+                    .filter(|p| self.available_update(p).is_some())
+                    .count();
+                let updates_label = match &self.update_manifest {
+                    Some(_) => format!("Updates ({})...", update_count),
+                    None => "Updates...".to_string(),
+                };
+                if ui
+                    .button(updates_label)
+                    .on_hover_text("Compare installed versions with a manifest of latest releases")
+                    .clicked()
+                {
+                    self.show_update_feed = true;
+                }
+
+                if ui.button("Protect List...").clicked() {
+                    self.show_protect_list = true;
+                }
+
+                ui.checkbox(&mut self.only_unsigned, "Only unsigned / ad-hoc")
+                    .on_hover_text("Show only plugins with no code signature or an ad-hoc one");
+
+                if let Some(last) = self.journal.operations.last() {
+                    if ui
+                        .button("Undo Last Operation")
+                        .on_hover_text(last.summary())
+                        .clicked()
+                    {
+                        self.undo_last_operation();
+                    }
+                }
+
+                let selected_count = self.selected_plugins.len() + self.selected_leftovers.len();
+                if selected_count > 0 {
                     ui.separator();
+                    if ui
+                        .button("Save as Profile...")
+                        .on_hover_text("Keep only these plugins enabled while the profile is active")
+                        .clicked()
+                    {
+                        self.new_profile_name = Some(String::new());
+                    }
                     if ui.button("Clear Selection").clicked() {
                         self.selected_plugins.clear();
                         self.selected_manufacturers.clear();
+                        self.selected_leftovers.clear();
                     }
                     if ui
-                        .button(format!("Move to Bin ({})", self.selected_plugins.len()))
+                        .button(format!(
+                            "Move to Bin ({}, {})",
+                            selected_count,
+                            format_size(self.selected_size())
+                        ))
                         .clicked()
                     {
                         self.show_confirmation = true;
@@ -35,46 +173,253 @@ impl eframe::App for PluginManager {
             ui.separator();
 
             egui::ScrollArea::vertical().show(ui, |ui| {
-                let plugins_data: Vec<(String, Vec<Plugin>)> = self
-                    .plugins
-                    .iter()
-                    .map(|(k, v)| (k.clone(), v.clone()))
-                    .collect();
+                let plugins_data = self.sorted_plugins();
+                let vendor_names: Vec<String> =
+                    plugins_data.iter().map(|(m, _)| m.clone()).collect();
+                let pointer_pos = ui.input(|i| i.pointer.interact_pos());
+                let pointer_released = ui.input(|i| i.pointer.any_released());
 
                 for (manufacturer, plugins) in plugins_data {
                     let mut manufacturer_selected = self.selected_manufacturers.contains(&manufacturer);
 
-                    ui.horizontal(|ui| {
-                        if ui.checkbox(&mut manufacturer_selected, "").changed() {
-                            self.toggle_manufacturer(&manufacturer);
+                    let header = ui
+                        .horizontal(|ui| {
+                            if ui.checkbox(&mut manufacturer_selected, "").changed() {
+                                self.toggle_manufacturer(&manufacturer);
+                            }
+                            let total_size: u64 = plugins.iter().map(|p| p.size).sum();
+                            let title = ui.strong(format!(
+                                "{} ({}) - {}",
+                                manufacturer,
+                                plugins.len(),
+                                format_size(total_size)
+                            ));
+                            let licensing = self.licensing.get(&manufacturer);
+                            for footprint in licensing.into_iter().flatten() {
+                                let mut hint = footprint.mechanism.description().to_string();
+                                for path in footprint.evidence.iter().take(10) {
+                                    hint.push_str(&format!("\n{}", path.display()));
+                                }
+                                if footprint.evidence.len() > 10 {
+                                    hint.push_str(&format!(
+                                        "\n...and {} more",
+                                        footprint.evidence.len() - 10
+                                    ));
+                                }
+                                ui.weak(footprint.mechanism.label()).on_hover_text(hint);
+                            }
+                            title.context_menu(|ui| {
+                                if ui.button("Rename...").clicked() {
+                                    self.renaming_vendor =
+                                        Some((manufacturer.clone(), manufacturer.clone()));
+                                    ui.close_menu();
+                                }
+                                ui.menu_button("Merge into", |ui| {
+                                    for other in vendor_names.iter().filter(|v| **v != manufacturer) {
+                                        if ui.button(other).clicked() {
+                                            self.rename_vendor(&manufacturer, other);
+                                            ui.close_menu();
+                                        }
+                                    }
+                                });
+                                if self.protect_list.vendors.contains(&manufacturer) {
+                                    if ui.button("Unprotect Vendor").clicked() {
+                                        self.unprotect_vendor(&manufacturer);
+                                        ui.close_menu();
+                                    }
+                                } else if ui.button("Protect Vendor").clicked() {
+                                    self.protect_vendor(&manufacturer);
+                                    ui.close_menu();
+                                }
+                            });
+                            if ui.small_button("Leftovers...").clicked() {
+                                self.find_vendor_leftovers(&manufacturer);
+                            }
+                        })
+                        .response;
+
+                    if self.dragging_plugin.is_some()
+                        && pointer_pos.is_some_and(|pos| header.rect.contains(pos))
+                    {
+                        ui.painter()
+                            .rect_stroke(header.rect, 2.0, ui.visuals().selection.stroke);
+                        if pointer_released {
+                            if let Some(plugin) = self.dragging_plugin.take() {
+                                self.reassign_plugin(&plugin, &manufacturer);
+                            }
                         }
-                        ui.strong(format!("{} ({})", manufacturer, plugins.len()));
-                    });
+                    }
 
                     ui.indent("plugins", |ui| {
                         for plugin in &plugins {
                             ui.horizontal(|ui| {
                                 let mut selected = self.selected_plugins.contains(&plugin.path);
-                                if ui.checkbox(&mut selected, "").changed() {
+                                let protected = self.is_protected(plugin);
+                                if ui
+                                    .add_enabled(!protected, egui::Checkbox::new(&mut selected, ""))
+                                    .changed()
+                                {
                                     self.toggle_plugin(plugin);
                                 }
 
-                                ui.label(&plugin.name);
+                                if let Some(rule) = self.protect_list.rule_for(plugin) {
+                                    let (icon, hint) = if protected {
+                                        ("🔒", "click to unlock it until the app is closed")
+                                    } else {
+                                        ("🔓", "unlocked for now; click to lock it again")
+                                    };
+                                    if ui
+                                        .small_button(icon)
+                                        .on_hover_text(format!("{}; {}", rule, hint))
+                                        .clicked()
+                                    {
+                                        self.toggle_unlock(plugin);
+                                    }
+                                }
+
+                                let mut name_text = egui::RichText::new(plugin.name.as_str());
+                                if plugin.disabled {
+                                    name_text = name_text.color(ui.visuals().weak_text_color());
+                                }
+                                let name = ui
+                                    .add(
+                                        egui::Label::new(name_text)
+                                            .sense(egui::Sense::click_and_drag()),
+                                    )
+                                    .on_hover_text(
+                                        "Click for details, or drag onto another vendor to move it there",
+                                    );
+                                if name.clicked() {
+                                    self.show_plugin_details(plugin);
+                                }
+                                if name.drag_started() {
+                                    self.dragging_plugin = Some(plugin.clone());
+                                }
+                                name.context_menu(|ui| {
+                                    ui.menu_button("Move to vendor", |ui| {
+                                        for other in
+                                            vendor_names.iter().filter(|v| **v != manufacturer)
+                                        {
+                                            if ui.button(other).clicked() {
+                                                self.reassign_plugin(plugin, other);
+                                                ui.close_menu();
+                                            }
+                                        }
+                                    });
+                                    if plugin.disabled {
+                                        if ui.button("Enable").clicked() {
+                                            self.enable_plugin(plugin);
+                                            ui.close_menu();
+                                        }
+                                    } else if ui.button("Disable").clicked() {
+                                        self.disable_plugin(plugin);
+                                        ui.close_menu();
+                                    }
+                                    if self.protect_list.protects(plugin) {
+                                        if ui.button("Remove Protection").clicked() {
+                                            self.unprotect_plugin(plugin);
+                                            ui.close_menu();
+                                        }
+                                    } else if ui.button("Protect").clicked() {
+                                        self.protect_plugin(plugin);
+                                        ui.close_menu();
+                                    }
+                                });
                                 ui.label(format!("({:?})", plugin.plugin_type));
 
+                                if plugin.disabled {
+                                    ui.weak("disabled");
+                                    if ui
+                                        .small_button("Enable")
+                                        .on_hover_text("Move it back to where DAWs load it from")
+                                        .clicked()
+                                    {
+                                        self.enable_plugin(plugin);
+                                    }
+                                }
+
+                                if plugin.manufacturer_source.is_inferred() {
+                                    ui.weak("vendor?").on_hover_text(format!(
+                                        "Vendor inferred from {} ({} confidence)",
+                                        plugin.manufacturer_source.label(),
+                                        plugin.manufacturer_source.confidence()
+                                    ));
+                                }
+
                                 if let Some(version) = &plugin.version {
                                     ui.label(format!("v{}", version));
                                 }
+
+                                if let Some(update) = self.available_update(plugin) {
+                                    let hint = format!("Version {} is out", update.latest_version);
+                                    match &update.download_url {
+                                        Some(url) => {
+                                            ui.hyperlink_to("update available", url)
+                                                .on_hover_text(format!("{}: {}", hint, url));
+                                        }
+                                        None => {
+                                            ui.colored_label(
+                                                egui::Color32::LIGHT_GREEN,
+                                                "update available",
+                                            )
+                                            .on_hover_text(hint);
+                                        }
+                                    }
+                                }
+
+                                if let Some(others) = self.version_mismatches.get(&plugin.path) {
+                                    ui.colored_label(egui::Color32::YELLOW, "version differs")
+                                        .on_hover_text(format!(
+                                            "Other formats aren't on the same version: {}",
+                                            others
+                                        ));
+                                }
+
+                                if let Some(usage) = self.project_usage.get(&plugin.path) {
+                                    ui.weak(format!(
+                                        "used in {} projects, last used in {}",
+                                        usage.project_count, usage.last_project
+                                    ));
+                                }
+
+                                for flag in self.daw_flags(plugin) {
+                                    ui.colored_label(
+                                        egui::Color32::RED,
+                                        format!("{} in {}", flag.status.label(), flag.daw),
+                                    );
+                                }
+
+                                if let Some(signature) = &plugin.signature {
+                                    if signature.status.is_anonymous() {
+                                        ui.colored_label(
+                                            egui::Color32::YELLOW,
+                                            signature.status.label(),
+                                        );
+                                    }
+                                }
+
+                                ui.weak(format_size(plugin.size));
                             });
                         }
                     });
 
                     ui.separator();
                 }
+
+                if self.dragging_plugin.is_some() {
+                    ctx.set_cursor_icon(egui::CursorIcon::Grabbing);
+                    if pointer_released {
+                        self.dragging_plugin = None;
+                    }
+                }
             });
         });
 
         if self.show_confirmation {
+            let plan = self
+                .pending_plan
+                .take()
+                .unwrap_or_else(|| self.removal_plan());
             egui::Window::new("Confirm Move to Bin")
                 .collapsible(false)
                 .resizable(false)
@@ -82,26 +427,69 @@ impl eframe::App for PluginManager {
                 .show(ctx, |ui| {
                     ui.label(format!(
                         "Are you sure you want to move these {} plugins to the bin?",
-                        self.selected_plugins.len()
+                        plan.count(ItemKind::Plugin)
                     ));
 
+                    let leftovers = plan.count(ItemKind::Leftover);
+                    if leftovers > 0 {
+                        ui.label(format!(
+                            "{} support folders will be moved along with them.",
+                            leftovers
+                        ));
+                    }
+
+                    ui.label(format!(
+                        "This will move {} files and free about {}.",
+                        plan.file_count,
+                        format_size(plan.total_size)
+                    ));
+
+                    for warning in &plan.warnings {
+                        ui.colored_label(egui::Color32::YELLOW, &warning.message)
+                            .on_hover_text(warning.path.display().to_string());
+                    }
+
                     ui.separator();
 
                     egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
-                        let mut sorted_paths: Vec<_> = self.selected_plugins.iter().collect();
-                        sorted_paths.sort();
-                        for path in sorted_paths {
-                            ui.label(
-                                path.file_name().unwrap_or_default().to_string_lossy().to_string(),
-                            );
+                        for item in &plan.items {
+                            egui::CollapsingHeader::new(format!(
+                                "{} ({} files, {})",
+                                item.name,
+                                item.files.len(),
+                                format_size(item.size)
+                            ))
+                            .id_source(&item.path)
+                            .show(ui, |ui| {
+                                ui.weak(item.path.display().to_string());
+                                for file in &item.files {
+                                    let relative =
+                                        file.path.strip_prefix(&item.path).unwrap_or(&file.path);
+                                    match &file.link_target {
+                                        Some(target) => ui.label(format!(
+                                            "{} -> {} (link only, target is kept)",
+                                            relative.display(),
+                                            target.display()
+                                        )),
+                                        None => ui.label(relative.display().to_string()),
+                                    };
+                                }
+                            });
                         }
                     });
 
                     ui.separator();
 
+                    ui.checkbox(
+                        &mut self.clear_daw_caches,
+                        "Also remove them from DAW plugin caches",
+                    )
+                    .on_hover_text("Changed cache files are backed up, so Undo restores them too");
+
                     ui.horizontal(|ui| {
                         if ui.button("Cancel").clicked() {
                             self.show_confirmation = false;
+                            self.packages_to_forget.clear();
                         }
 
                         if ui.button("Move to Bin").clicked() {
@@ -110,6 +498,641 @@ impl eframe::App for PluginManager {
                         }
                     });
                 });
+            if self.show_confirmation {
+                self.pending_plan = Some(plan);
+            }
+        }
+
+        if let Some(mut name) = self.new_profile_name.take() {
+            let mut keep_open = true;
+            egui::Window::new("Save Profile")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    let count = self
+                        .plugins
+                        .values()
+                        .flatten()
+                        .filter(|p| self.selected_plugins.contains(&p.path))
+                        .count();
+                    ui.label(format!(
+                        "Save the {} selected plugins as a profile named:",
+                        count
+                    ));
+                    ui.text_edit_singleline(&mut name);
+                    ui.horizontal(|ui| {
+                        if ui.button("Cancel").clicked() {
+                            keep_open = false;
+                        }
+                        let name = name.trim();
+                        if ui
+                            .add_enabled(!name.is_empty(), egui::Button::new("Save"))
+                            .clicked()
+                        {
+                            self.save_selection_as_profile(name);
+                            keep_open = false;
+                        }
+                    });
+                });
+            if keep_open {
+                self.new_profile_name = Some(name);
+            }
+        }
+
+        if let Some(switch) = self.profile_switch.clone() {
+            let mut open = true;
+            egui::Window::new(format!("Switch to {}", switch.name))
+                .open(&mut open)
+                .collapsible(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    if switch.is_empty() {
+                        ui.label("Every plugin is already where this profile wants it.");
+                    } else {
+                        ui.label(format!(
+                            "{} plugins will be disabled and {} enabled.",
+                            switch.to_disable.len(),
+                            switch.to_enable.len()
+                        ));
+                    }
+
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        let groups = [
+                            ("Disable", &switch.to_disable),
+                            ("Enable", &switch.to_enable),
+                            ("Protected, left alone", &switch.skipped),
+                        ];
+                        for (title, plugins) in groups {
+                            if plugins.is_empty() {
+                                continue;
+                            }
+                            egui::CollapsingHeader::new(format!("{} ({})", title, plugins.len()))
+                                .show(ui, |ui| {
+                                    for plugin in plugins {
+                                        ui.label(format!(
+                                            "{} - {} ({:?})",
+                                            plugin.manufacturer, plugin.name, plugin.plugin_type
+                                        ));
+                                    }
+                                });
+                        }
+                    });
+
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("Switch").clicked() {
+                            self.apply_profile_switch();
+                        }
+                        if ui.button("Select Its Plugins").clicked() {
+                            self.select_profile_plugins(&switch.name);
+                            self.profile_switch = None;
+                        }
+                        if ui.button("Delete Profile").clicked() {
+                            self.delete_profile(&switch.name);
+                            self.profile_switch = None;
+                        }
+                    });
+                });
+            if !open {
+                self.profile_switch = None;
+            }
+        }
+
+        if self.show_rule_window {
+            let mut open = true;
+            egui::Window::new("Select by Rule")
+                .open(&mut open)
+                .collapsible(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Rule");
+                        ui.text_edit_singleline(&mut self.rule_text)
+                            .on_hover_text(RULE_HELP);
+                    });
+                    ui.weak("e.g. format:vst2 and has-format:vst3, or unmodified:2y");
+
+                    ui.horizontal(|ui| {
+                        for (label, replace) in [("Select", true), ("Add to Selection", false)] {
+                            if ui.button(label).clicked() {
+                                self.rule_message = Some(match self.apply_rule_text(replace) {
+                                    Ok(count) => format!("{} plugins matched.", count),
+                                    Err(e) => format!("{}", e),
+                                });
+                            }
+                        }
+                    });
+                    if let Some(message) = &self.rule_message {
+                        ui.label(message);
+                    }
+
+                    ui.separator();
+                    ui.strong("Saved selections");
+                    let names: Vec<String> =
+                        self.saved_selections.selections.keys().cloned().collect();
+                    if names.is_empty() {
+                        ui.weak("None saved yet.");
+                    }
+                    for name in names {
+                        ui.horizontal(|ui| {
+                            ui.label(&name);
+                            if ui.small_button("Load").clicked() {
+                                self.load_selection(&name);
+                            }
+                            if ui.small_button("Delete").clicked() {
+                                self.delete_saved_selection(&name);
+                            }
+                        });
+                    }
+                    ui.horizontal(|ui| {
+                        ui.text_edit_singleline(&mut self.new_selection_name);
+                        let name = self.new_selection_name.trim().to_string();
+                        if ui
+                            .add_enabled(!name.is_empty(), egui::Button::new("Save Current"))
+                            .clicked()
+                        {
+                            self.save_selection(&name);
+                            self.new_selection_name.clear();
+                        }
+                    });
+                });
+            if !open {
+                self.show_rule_window = false;
+            }
+        }
+
+        if self.show_protect_list {
+            let mut open = true;
+            egui::Window::new("Protect List")
+                .open(&mut open)
+                .collapsible(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.label("Protected plugins can't be selected, moved to the bin or moved.");
+                    if let Some(error) = self.protect_list_error.clone() {
+                        ui.colored_label(egui::Color32::RED, error);
+                        ui.label("Nothing can be removed or moved until the file is fixed.");
+                        if ui.button("Try Again").clicked() {
+                            self.reload_protect_list();
+                        }
+                        return;
+                    }
+                    if self.protect_list.is_empty() {
+                        ui.weak("Nothing is protected yet.");
+                    }
+
+                    let mut changed = false;
+                    egui::Grid::new("protect_list").num_columns(3).show(ui, |ui| {
+                        let rules = [
+                            ("Vendor", &mut self.protect_list.vendors),
+                            ("Bundle ID", &mut self.protect_list.bundle_ids),
+                            ("Path", &mut self.protect_list.path_globs),
+                        ];
+                        for (kind, entries) in rules {
+                            let mut removed = None;
+                            for entry in entries.iter() {
+                                ui.weak(kind);
+                                ui.label(entry);
+                                if ui.small_button("Remove").clicked() {
+                                    removed = Some(entry.clone());
+                                }
+                                ui.end_row();
+                            }
+                            if let Some(entry) = removed {
+                                entries.remove(&entry);
+                                changed = true;
+                            }
+                        }
+                    });
+
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.text_edit_singleline(&mut self.new_protect_glob)
+                            .on_hover_text("* and ? match within a folder, ** across folders");
+                        if ui.button("Add Path").clicked()
+                            && !self.new_protect_glob.trim().is_empty()
+                        {
+                            let glob = self.new_protect_glob.trim().to_string();
+                            self.protect_list.path_globs.insert(glob);
+                            self.new_protect_glob.clear();
+                            changed = true;
+                        }
+                    });
+
+                    if changed {
+                        self.save_protect_list();
+                    }
+                });
+            if !open {
+                self.show_protect_list = false;
+            }
+        }
+
+        if self.show_update_feed {
+            let mut open = true;
+            egui::Window::new("Update Feed")
+                .open(&mut open)
+                .collapsible(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.label("A JSON manifest of the latest version of each product.");
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.update_source)
+                                .hint_text("File path or https:// URL"),
+                        );
+                        if ui.button("Browse...").clicked() {
+                            self.choose_update_manifest();
+                        }
+                        let checking = self.update_check.is_some();
+                        if ui
+                            .add_enabled(
+                                !checking && !self.update_source.trim().is_empty(),
+                                egui::Button::new("Check"),
+                            )
+                            .clicked()
+                        {
+                            self.check_for_updates();
+                        }
+                        if checking {
+                            ui.spinner();
+                        }
+                    });
+
+                    if let Some(error) = &self.update_error {
+                        ui.colored_label(egui::Color32::RED, error);
+                    }
+
+                    let Some(manifest) = &self.update_manifest else {
+                        return;
+                    };
+                    let updates: Vec<_> = self
+                        .plugins
+                        .values()
+                        .flatten()
+                        .filter_map(|p| manifest.update_for(p).map(|entry| (p, entry)))
+                        .collect();
+                    ui.label(format!(
+                        "{} products listed, {} plugins have updates.",
+                        manifest.products.len(),
+                        updates.len()
+                    ));
+
+                    ui.separator();
+
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        egui::Grid::new("updates").striped(true).show(ui, |ui| {
+                            for (plugin, entry) in updates {
+                                ui.label(format!(
+                                    "{} - {} ({:?})",
+                                    plugin.manufacturer, plugin.name, plugin.plugin_type
+                                ));
+                                ui.label(format!(
+                                    "{} -> {}",
+                                    plugin.version.as_deref().unwrap_or_default(),
+                                    entry.latest_version
+                                ));
+                                match &entry.download_url {
+                                    Some(url) => ui.hyperlink_to("Download", url),
+                                    None => ui.weak("No download link"),
+                                };
+                                ui.end_row();
+                            }
+                        });
+                    });
+                });
+            if !open {
+                self.show_update_feed = false;
+            }
+        }
+
+        if self.show_duplicates {
+            let mut open = true;
+            egui::Window::new("Duplicate Plugins")
+                .open(&mut open)
+                .collapsible(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    if self.duplicates.is_empty() {
+                        ui.label("No duplicate plugins found.");
+                        return;
+                    }
+
+                    ui.label(format!(
+                        "Found {} plugins installed in more than one location.",
+                        self.duplicates.len()
+                    ));
+
+                    ui.separator();
+
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for set in &self.duplicates {
+                            let preferred = set.preferred_plugin();
+                            ui.strong(format!(
+                                "{} - {} ({:?})",
+                                preferred.manufacturer, preferred.name, preferred.plugin_type
+                            ));
+                            ui.indent("duplicates", |ui| {
+                                for (i, plugin) in set.plugins.iter().enumerate() {
+                                    let version = plugin
+                                        .version
+                                        .as_deref()
+                                        .map(|v| format!("v{}", v))
+                                        .unwrap_or_else(|| "unknown version".to_string());
+                                    let marker = if i == set.preferred {
+                                        " - likely loaded"
+                                    } else {
+                                        ""
+                                    };
+                                    ui.label(format!(
+                                        "{}{}: {}",
+                                        version,
+                                        marker,
+                                        plugin.path.display()
+                                    ));
+                                }
+                            });
+                        }
+                    });
+
+                    ui.separator();
+
+                    if ui.button("Move Older Copies to Bin").clicked() {
+                        self.select_redundant_duplicates();
+                        self.show_duplicates = false;
+                        self.show_confirmation = true;
+                    }
+                });
+            if !open {
+                self.show_duplicates = false;
+            }
+        }
+
+        if self.show_format_cleanup {
+            let mut open = true;
+            egui::Window::new("Clean Up Formats")
+                .open(&mut open)
+                .collapsible(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Keep:");
+                        for format in PluginType::ALL {
+                            let mut keep = self.format_policy.keeps(&format);
+                            if ui.checkbox(&mut keep, format!("{:?}", format)).changed() {
+                                self.toggle_policy_format(format);
+                            }
+                        }
+                    });
+
+                    ui.separator();
+
+                    if self.redundant_formats.is_empty() {
+                        ui.label("Every product is only installed in the formats you keep.");
+                        return;
+                    }
+
+                    let size: u64 = self.redundant_formats.iter().map(|r| r.plugin.size).sum();
+                    ui.label(format!(
+                        "{} copies in other formats take up {}.",
+                        self.redundant_formats.len(),
+                        format_size(size)
+                    ));
+
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for redundant in &self.redundant_formats {
+                            let plugin = &redundant.plugin;
+                            ui.strong(format!(
+                                "{} - {} ({:?})",
+                                plugin.manufacturer, plugin.name, plugin.plugin_type
+                            ));
+                            ui.indent(&plugin.path, |ui| {
+                                ui.label(redundant.reason());
+                                ui.weak(plugin.path.display().to_string());
+                                if self.is_protected(plugin) {
+                                    ui.colored_label(
+                                        egui::Color32::YELLOW,
+                                        "Protected, so it will be kept",
+                                    );
+                                }
+                            });
+                        }
+                    });
+
+                    ui.separator();
+
+                    if ui.button("Move Redundant Formats to Bin").clicked() {
+                        self.select_redundant_formats();
+                        self.show_format_cleanup = false;
+                        self.show_confirmation = true;
+                    }
+                });
+            if !open {
+                self.show_format_cleanup = false;
+            }
+        }
+
+        if let Some(vendor) = self.leftovers_vendor.clone() {
+            let mut open = true;
+            let leftovers = self.leftovers.get(&vendor).cloned().unwrap_or_default();
+            egui::Window::new(format!("{} Leftovers", vendor))
+                .open(&mut open)
+                .collapsible(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    if leftovers.is_empty() {
+                        ui.label("No presets or support files found for this vendor.");
+                        return;
+                    }
+
+                    ui.label("Tick the folders to move to the bin along with the plugins.");
+                    ui.separator();
+
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for leftover in &leftovers {
+                            ui.horizontal(|ui| {
+                                let mut selected = self.selected_leftovers.contains(&leftover.path);
+                                if ui.checkbox(&mut selected, "").changed() {
+                                    self.toggle_leftover(&leftover.path);
+                                }
+                                ui.label(format!(
+                                    "{} ({}, {})",
+                                    leftover.path.display(),
+                                    leftover.kind.label(),
+                                    format_size(leftover.size)
+                                ));
+                                if leftover.has_licence_files {
+                                    ui.colored_label(
+                                        egui::Color32::YELLOW,
+                                        "contains licence files",
+                                    );
+                                }
+                            });
+                        }
+                    });
+                });
+            if !open {
+                self.leftovers_vendor = None;
+            }
+        }
+
+        if let Some((original, mut new_name)) = self.renaming_vendor.clone() {
+            let mut open = true;
+            let mut apply = false;
+            let mut cancel = false;
+            egui::Window::new("Rename Vendor")
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.label(format!("New name for {}:", original));
+                    ui.text_edit_singleline(&mut new_name);
+                    ui.weak("If a vendor with that name already exists, the two are merged.");
+                    ui.horizontal(|ui| {
+                        cancel = ui.button("Cancel").clicked();
+                        apply = ui.button("Rename").clicked();
+                    });
+                });
+
+            if apply {
+                self.rename_vendor(&original, &new_name);
+                self.renaming_vendor = None;
+            } else if cancel || !open {
+                self.renaming_vendor = None;
+            } else {
+                self.renaming_vendor = Some((original, new_name));
+            }
+        }
+
+        if let Some(plugin) = self.details_plugin.clone() {
+            let mut open = true;
+            egui::Window::new("Plugin Details")
+                .open(&mut open)
+                .collapsible(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    self.plugin_details_ui(ui, &plugin);
+                });
+            if !open {
+                self.details_plugin = None;
+            }
+        }
+
+        if let Some(error_message) = self.protect_list_error.clone() {
+            egui::Window::new("Protect List Error")
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 40.0))
+                .show(ctx, |ui| {
+                    ui.label(error_message);
+                    ui.label(
+                        "Every plugin is treated as protected, and the protect list won't be \
+                         saved, until the file is fixed.",
+                    );
+                    ui.add_space(10.0);
+                    if ui.button("Try Again").clicked() {
+                        self.reload_protect_list();
+                    }
+                });
+        }
+
+        if let Some(error_message) = self.undo_error.clone() {
+            egui::Window::new("Undo Error")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.label(error_message);
+                    ui.add_space(10.0);
+                    if ui.button("OK").clicked() {
+                        self.undo_error = None;
+                    }
+                });
+        }
+
+        if let Some(results) = self.trash_results.clone() {
+            let mut open = true;
+            egui::Window::new("Move to Bin Results")
+                .open(&mut open)
+                .collapsible(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    let failed: Vec<_> = results
+                        .iter()
+                        .filter(|r| r.outcome != TrashOutcome::Moved)
+                        .collect();
+                    ui.label(format!(
+                        "{} of {} items were moved to the bin. These could not be:",
+                        results.len() - failed.len(),
+                        results.len()
+                    ));
+                    ui.separator();
+
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        egui::Grid::new("trash_results").num_columns(2).show(ui, |ui| {
+                            for result in failed {
+                                ui.label(
+                                    result
+                                        .path
+                                        .file_name()
+                                        .unwrap_or_default()
+                                        .to_string_lossy()
+                                        .to_string(),
+                                )
+                                .on_hover_text(result.path.display().to_string());
+                                ui.colored_label(egui::Color32::YELLOW, result.outcome.label());
+                                ui.end_row();
+                            }
+                        });
+                    });
+
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("Retry Failed").clicked() {
+                            self.retry_failed_trash();
+                        }
+                        let denied = results
+                            .iter()
+                            .any(|r| r.outcome == TrashOutcome::PermissionDenied);
+                        if let Some(elevator) = self.elevator.as_ref().filter(|_| denied) {
+                            if ui
+                                .button("Retry as Administrator")
+                                .on_hover_text(format!(
+                                    "Moves only the items that were denied, asking through {}",
+                                    elevator.name()
+                                ))
+                                .clicked()
+                            {
+                                self.retry_failed_as_admin();
+                            }
+                        }
+                        if ui.button("Copy Report").clicked() {
+                            let report = self.trash_report();
+                            ui.output_mut(|o| o.copied_text = report);
+                        }
+                        if ui.button("Close").clicked() {
+                            self.trash_results = None;
+                        }
+                    });
+                });
+            if !open {
+                self.trash_results = None;
+            }
+        }
+
+        if let Some(error_message) = self.move_error.clone() {
+            egui::Window::new("Move Error")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.label(error_message);
+                    ui.add_space(10.0);
+                    if ui.button("OK").clicked() {
+                        self.move_error = None;
+                    }
+                });
         }
 
         if let Some(error_message) = self.deletion_error.clone() {
@@ -129,3 +1152,147 @@ impl eframe::App for PluginManager {
         }
     }
 }
+
+impl PluginManager {
+    fn plugin_details_ui(&mut self, ui: &mut egui::Ui, plugin: &Plugin) {
+        egui::Grid::new("plugin_details").num_columns(2).show(ui, |ui| {
+            ui.label("Name");
+            ui.label(&plugin.name);
+            ui.end_row();
+
+            ui.label("Vendor");
+            ui.label(format!(
+                "{} (from {}, {} confidence)",
+                plugin.manufacturer,
+                plugin.manufacturer_source.label(),
+                plugin.manufacturer_source.confidence()
+            ));
+            ui.end_row();
+
+            if let Some(copyright) = &plugin.copyright {
+                ui.label("Copyright");
+                ui.label(copyright);
+                ui.end_row();
+            }
+
+            ui.label("Format");
+            ui.label(format!("{:?}", plugin.plugin_type));
+            ui.end_row();
+
+            ui.label("Version");
+            ui.label(plugin.version.as_deref().unwrap_or("Unknown"));
+            ui.end_row();
+
+            if let Some(bundle_id) = &plugin.bundle_id {
+                ui.label("Identifier");
+                ui.label(bundle_id);
+                ui.end_row();
+            }
+
+            if let Some(signature) = &plugin.signature {
+                ui.label("Signature");
+                ui.label(signature.status.label());
+                ui.end_row();
+
+                if let Some(publisher) = signature.publisher() {
+                    ui.label("Publisher");
+                    ui.label(publisher);
+                    ui.end_row();
+                }
+
+                if let Some(signer) = &signature.signer {
+                    ui.label("Signed by");
+                    ui.label(signer);
+                    ui.end_row();
+                }
+
+                if signature.valid_from.is_some() || signature.valid_until.is_some() {
+                    ui.label("Certificate valid");
+                    ui.label(format!(
+                        "{} to {}",
+                        signature.valid_from.as_deref().unwrap_or("?"),
+                        signature.valid_until.as_deref().unwrap_or("?")
+                    ));
+                    ui.end_row();
+                }
+
+                if let Some(team_id) = &signature.team_id {
+                    ui.label("Team ID");
+                    ui.label(team_id);
+                    ui.end_row();
+                }
+            }
+
+            ui.label("Path");
+            ui.label(plugin.path.display().to_string());
+            ui.end_row();
+
+            for flag in self.daw_flags(plugin) {
+                ui.label(flag.daw);
+                ui.colored_label(egui::Color32::RED, flag.status.label());
+                ui.end_row();
+            }
+        });
+
+        if let Some(entry) = self.details_uninstaller.clone() {
+            ui.separator();
+            let version = entry
+                .display_version
+                .as_deref()
+                .map(|v| format!(" v{}", v))
+                .unwrap_or_default();
+            if entry.has_uninstaller() {
+                ui.label(format!(
+                    "Installed by: {}{} (uninstaller available)",
+                    entry.display_name, version
+                ));
+                if ui.button("Run Uninstaller").clicked() {
+                    self.run_details_uninstaller();
+                }
+            } else {
+                ui.label(format!("Installed by: {}{}", entry.display_name, version));
+            }
+
+            if let Some(error) = &self.uninstaller_error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        }
+
+        let package = self.receipts.as_ref().and_then(|receipts| {
+            let package = receipts.package_for(&plugin.path)?;
+            Some((package.clone(), receipts.removal_paths(package)))
+        });
+
+        if let Some((package, removal_paths)) = package {
+            ui.separator();
+            ui.label(format!(
+                "Installed by package {}{} ({})",
+                package.receipt.package_id,
+                package
+                    .receipt
+                    .version
+                    .as_deref()
+                    .map(|v| format!(" v{}", v))
+                    .unwrap_or_default(),
+                format_size(package.installed_size())
+            ));
+
+            egui::CollapsingHeader::new(format!(
+                "{} items from this package",
+                removal_paths.len()
+            ))
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    for path in &removal_paths {
+                        ui.label(path.display().to_string());
+                    }
+                });
+            });
+
+            if ui.button("Uninstall Package").clicked() {
+                self.uninstall_package(&package);
+                self.details_plugin = None;
+            }
+        }
+    }
+}
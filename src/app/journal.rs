@@ -0,0 +1,229 @@
+use super::state::PluginManager;
+use crate::plugin::Plugin;
+use crate::scanner::CacheBackup;
+use crate::utils::config::{load_json, save_json};
+use crate::utils::elevation::{elevated_restore, Elevator};
+use crate::utils::error::Result;
+use crate::utils::fs::restore_from_trash;
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const JOURNAL_FILE: &str = "journal.json";
+const MAX_OPERATIONS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OperationKind {
+    /// Items were moved to the platform's bin.
+    Trash,
+    /// Items were moved to another folder, recorded in `JournalEntry::moved_to`.
+    Move,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub original: PathBuf,
+    /// Where the item went: the destination of a move, or its place in the bin when
+    /// the bin doesn't record that itself.
+    pub moved_to: Option<PathBuf>,
+    pub size: u64,
+    /// The plugin as it was listed before the operation, if the item was a plugin.
+    pub plugin: Option<Plugin>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operation {
+    pub kind: OperationKind,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub entries: Vec<JournalEntry>,
+    /// DAW cache files changed along with the operation.
+    pub cache_backup: Option<CacheBackup>,
+}
+
+impl Operation {
+    pub fn new(kind: OperationKind, entries: Vec<JournalEntry>) -> Self {
+        Self {
+            kind,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            entries,
+            cache_backup: None,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let action = match self.kind {
+            OperationKind::Trash => "Moved to the bin",
+            OperationKind::Move => "Moved",
+        };
+        let elapsed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs().saturating_sub(self.timestamp))
+            .unwrap_or_default();
+        format!(
+            "{} {} items {}",
+            action,
+            self.entries.len(),
+            format_age(elapsed)
+        )
+    }
+
+    /// Puts every item it can back where it was and restores any DAW caches, even if
+    /// some items can't be. Only what failed is left in the operation afterwards, so
+    /// undoing it again retries just that.
+    fn undo(&mut self, elevator: Option<&dyn Elevator>) -> Result<()> {
+        let failures = match self.kind {
+            OperationKind::Trash => {
+                let items: Vec<(PathBuf, Option<PathBuf>)> = self
+                    .entries
+                    .iter()
+                    .map(|e| (e.original.clone(), e.moved_to.clone()))
+                    .collect();
+                let failures = restore_from_trash(&items);
+                match elevator {
+                    Some(elevator) => self.restore_elevated(elevator, failures),
+                    None => failures,
+                }
+            }
+            OperationKind::Move => self
+                .entries
+                .iter()
+                .filter_map(|e| {
+                    let moved_to = e.moved_to.as_ref()?;
+                    std::fs::rename(moved_to, &e.original)
+                        .err()
+                        .map(|err| (e.original.clone(), err.to_string()))
+                })
+                .collect(),
+        };
+        self.entries
+            .retain(|e| failures.iter().any(|(path, _)| *path == e.original));
+
+        let mut errors: Vec<String> = failures
+            .iter()
+            .map(|(path, e)| format!("{}: {}", path.display(), e))
+            .collect();
+        match self.cache_backup.as_ref().map(|b| b.restore()) {
+            Some(Err(e)) => errors.push(format!("{:#}", e)),
+            Some(Ok(())) => self.cache_backup = None,
+            None => {}
+        }
+
+        if !errors.is_empty() {
+            bail!("{}", errors.join("\n"));
+        }
+        Ok(())
+    }
+
+    /// Tries the items that couldn't be put back again through the administrator
+    /// prompt, such as package receipts binned from a system folder. Only items still
+    /// in the bin with nothing in their old place are tried.
+    fn restore_elevated(
+        &self,
+        elevator: &dyn Elevator,
+        failures: Vec<(PathBuf, String)>,
+    ) -> Vec<(PathBuf, String)> {
+        let exists = |p: &Path| std::fs::symlink_metadata(p).is_ok();
+        let items: Vec<(PathBuf, PathBuf)> = self
+            .entries
+            .iter()
+            .filter(|e| failures.iter().any(|(path, _)| *path == e.original))
+            .filter_map(|e| Some((e.moved_to.clone()?, e.original.clone())))
+            .filter(|(trashed, original)| exists(trashed) && !exists(original))
+            .collect();
+        if items.is_empty() {
+            return failures;
+        }
+
+        let run = elevated_restore(elevator, &items);
+        failures
+            .into_iter()
+            .filter(|(path, _)| !exists(path))
+            .map(|(path, e)| match &run {
+                Err(run) => (path, format!("{:#}", run)),
+                Ok(()) => (path, e),
+            })
+            .collect()
+    }
+}
+
+/// Every destructive operation, oldest first, saved to `journal.json` so undo works
+/// after a restart.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Journal {
+    pub operations: Vec<Operation>,
+}
+
+impl Journal {
+    pub fn load() -> Self {
+        load_json(JOURNAL_FILE)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        save_json(JOURNAL_FILE, self)
+    }
+
+    /// Adds an operation, dropping the oldest ones (and their cache backups) once the
+    /// journal is full.
+    pub fn record(&mut self, operation: Operation) {
+        self.operations.push(operation);
+        if self.operations.len() > MAX_OPERATIONS {
+            let excess = self.operations.len() - MAX_OPERATIONS;
+            for old in self.operations.drain(..excess) {
+                if let Some(backup) = old.cache_backup {
+                    let _ = std::fs::remove_dir_all(backup.dir);
+                }
+            }
+        }
+    }
+}
+
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86400),
+    }
+}
+
+impl PluginManager {
+    pub(super) fn record_operation(&mut self, operation: Operation) {
+        if operation.entries.is_empty() && operation.cache_backup.is_none() {
+            return;
+        }
+        self.journal.record(operation);
+        if let Err(e) = self.journal.save() {
+            eprintln!("Error saving operation journal: {}", e);
+        }
+    }
+
+    pub fn undo_last_operation(&mut self) {
+        let Some(operation) = self.journal.operations.last_mut() else {
+            return;
+        };
+        let elevator = self.elevator.as_deref();
+
+        // The operation stays in the journal until everything in it is restored, so
+        // nothing that failed is lost and Undo can be tried again.
+        match operation.undo(elevator) {
+            Ok(()) => {
+                self.journal.operations.pop();
+            }
+            Err(e) => {
+                self.undo_error = Some(format!(
+                    "Could not fully undo the last operation; Undo again to retry the rest.\n{}",
+                    e
+                ));
+            }
+        }
+        if let Err(e) = self.journal.save() {
+            eprintln!("Error saving operation journal: {}", e);
+        }
+        self.scan_plugins();
+    }
+}
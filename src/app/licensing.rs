@@ -0,0 +1,18 @@
+use super::state::PluginManager;
+use crate::scanner::detect_licensing;
+
+impl PluginManager {
+    pub(super) fn refresh_licensing(&mut self) {
+        self.licensing = self
+            .plugins
+            .iter()
+            .map(|(vendor, plugins)| {
+                (
+                    vendor.clone(),
+                    detect_licensing(&self.licence_detectors, vendor, plugins),
+                )
+            })
+            .filter(|(_, footprints)| !footprints.is_empty())
+            .collect();
+    }
+}
@@ -1,5 +1,17 @@
+mod disable;
+mod formats;
 mod gui;
+mod journal;
+mod licensing;
+mod plan;
+mod profiles;
+mod projects;
+mod protection;
+mod rules;
 mod selection;
 mod state;
+mod updates;
+mod vendors;
+mod versions;
 
 pub use state::PluginManager;
@@ -0,0 +1,339 @@
+use super::state::PluginManager;
+use crate::utils::fs::{is_system_path, needs_admin};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    Plugin,
+    Leftover,
+    /// Anything else that was selected, such as the files of an installer package.
+    Other,
+}
+
+/// A file that moving an item to the bin takes with it. Symlinks are never followed:
+/// the link itself goes to the bin and `link_target` is left where it is.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedFile {
+    pub path: PathBuf,
+    pub size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_target: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanItem {
+    pub path: PathBuf,
+    pub kind: ItemKind,
+    pub name: String,
+    pub size: u64,
+    pub files: Vec<PlannedFile>,
+}
+
+impl PlanItem {
+    fn new(path: &Path, kind: ItemKind, name: String) -> Self {
+        let files: Vec<PlannedFile> = WalkDir::new(path)
+            .follow_links(false)
+            .follow_root_links(false)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter_map(|entry| {
+                let file_type = entry.file_type();
+                if file_type.is_symlink() {
+                    Some(PlannedFile {
+                        path: entry.path().to_path_buf(),
+                        size: 0,
+                        link_target: std::fs::read_link(entry.path()).ok(),
+                    })
+                } else if file_type.is_file() {
+                    Some(PlannedFile {
+                        path: entry.path().to_path_buf(),
+                        size: entry.metadata().map(|m| m.len()).unwrap_or_default(),
+                        link_target: None,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        Self {
+            path: path.to_path_buf(),
+            kind,
+            name,
+            size: files.iter().map(|f| f.size).sum(),
+            files,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    /// The item is part of the operating system.
+    SystemPath,
+    /// A scanned project still uses the plugin.
+    InUse,
+    /// The item's folder isn't writable without administrator rights.
+    NeedsAdmin,
+    /// The item no longer exists.
+    Missing,
+    /// The item is on the protect list, so it will be left where it is.
+    Protected,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanWarning {
+    pub kind: WarningKind,
+    pub path: PathBuf,
+    pub message: String,
+}
+
+/// Exactly what moving the current selection to the bin would touch. Building it
+/// changes nothing on disk.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RemovalPlan {
+    pub items: Vec<PlanItem>,
+    pub file_count: usize,
+    pub total_size: u64,
+    pub warnings: Vec<PlanWarning>,
+}
+
+impl RemovalPlan {
+    /// How many of the items that will actually be moved are of `kind`.
+    pub fn count(&self, kind: ItemKind) -> usize {
+        self.items.iter().filter(|item| item.kind == kind).count()
+    }
+
+    fn warn(&mut self, kind: WarningKind, path: &Path, message: String) {
+        self.warnings.push(PlanWarning {
+            kind,
+            path: path.to_path_buf(),
+            message,
+        });
+    }
+}
+
+impl PluginManager {
+    pub fn removal_plan(&self) -> RemovalPlan {
+        let mut paths: Vec<&PathBuf> = self
+            .selected_plugins
+            .iter()
+            .chain(self.selected_leftovers.iter())
+            .collect();
+        paths.sort();
+
+        let mut plan = RemovalPlan::default();
+        for path in paths {
+            let plugin = self.plugins.values().flatten().find(|p| &p.path == path);
+            let (kind, name) = match plugin {
+                Some(plugin) => (ItemKind::Plugin, plugin.name.clone()),
+                None if self.selected_leftovers.contains(path) => {
+                    (ItemKind::Leftover, display_name(path))
+                }
+                None => (ItemKind::Other, display_name(path)),
+            };
+
+            if self.is_protected_path(path) {
+                plan.warn(
+                    WarningKind::Protected,
+                    path,
+                    format!("{} is protected and will be skipped", name),
+                );
+                continue;
+            }
+            if std::fs::symlink_metadata(path).is_err() {
+                plan.warn(
+                    WarningKind::Missing,
+                    path,
+                    format!("{} no longer exists", name),
+                );
+                continue;
+            }
+
+            let system = is_system_path(path)
+                || plugin
+                    .and_then(|p| p.bundle_id.as_deref())
+                    .is_some_and(|id| id.starts_with("com.apple."));
+            if system {
+                plan.warn(
+                    WarningKind::SystemPath,
+                    path,
+                    format!("{} is part of the operating system", name),
+                );
+            }
+            if let Some(usage) = self.project_usage.get(path) {
+                plan.warn(
+                    WarningKind::InUse,
+                    path,
+                    format!(
+                        "{} is used in {} projects, last used in {}",
+                        name, usage.project_count, usage.last_project
+                    ),
+                );
+            }
+            if needs_admin(path) {
+                plan.warn(
+                    WarningKind::NeedsAdmin,
+                    path,
+                    format!("{} needs administrator rights to remove", name),
+                );
+            }
+
+            let item = PlanItem::new(path, kind, name);
+            plan.file_count += item.files.len();
+            plan.total_size += item.size;
+            plan.items.push(item);
+        }
+        plan
+    }
+}
+
+fn display_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::{Plugin, PluginType};
+
+    fn kinds(plan: &RemovalPlan) -> Vec<WarningKind> {
+        plan.warnings.iter().map(|w| w.kind).collect()
+    }
+
+    fn add_plugin(manager: &mut PluginManager, plugin: Plugin) {
+        manager.selected_plugins.insert(plugin.path.clone());
+        manager
+            .plugins
+            .entry(plugin.manufacturer.clone())
+            .or_default()
+            .push(plugin);
+    }
+
+    #[test]
+    fn counts_files_and_links_without_following_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("Synth.vst3");
+        std::fs::create_dir_all(bundle.join("Contents/Resources")).unwrap();
+        std::fs::write(bundle.join("Contents/Info.plist"), [0; 10]).unwrap();
+        std::fs::write(bundle.join("Contents/Resources/Presets.bin"), [0; 20]).unwrap();
+        let outside = dir.path().join("Shared Library");
+        std::fs::write(&outside, [0; 1000]).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&outside, bundle.join("Contents/Library")).unwrap();
+        let leftover = dir.path().join("Synth.log");
+        std::fs::write(&leftover, [0; 5]).unwrap();
+
+        let mut manager = PluginManager::test();
+        add_plugin(
+            &mut manager,
+            Plugin {
+                path: bundle.clone(),
+                ..Plugin::test("Synth", "Acme", PluginType::VST3)
+            },
+        );
+        manager.selected_leftovers.insert(leftover.clone());
+
+        let plan = manager.removal_plan();
+        assert!(plan.warnings.is_empty());
+        assert_eq!(plan.count(ItemKind::Plugin), 1);
+        assert_eq!(plan.count(ItemKind::Leftover), 1);
+        assert_eq!(plan.total_size, 35);
+
+        let synth = plan.items.iter().find(|i| i.path == bundle).unwrap();
+        assert_eq!(synth.name, "Synth");
+        assert_eq!(synth.size, 30);
+        #[cfg(unix)]
+        {
+            assert_eq!(plan.file_count, 4);
+            let link = synth
+                .files
+                .iter()
+                .find(|f| f.link_target.is_some())
+                .unwrap();
+            assert_eq!(link.size, 0);
+            assert_eq!(link.link_target.as_deref(), Some(outside.as_path()));
+        }
+    }
+
+    #[test]
+    fn protected_and_missing_items_are_left_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let protected = dir.path().join("CLA-76.vst3");
+        std::fs::create_dir(&protected).unwrap();
+        std::fs::write(protected.join("binary"), [0; 100]).unwrap();
+
+        let mut manager = PluginManager::test();
+        manager.protect_list.vendors.insert("Waves".to_string());
+        add_plugin(
+            &mut manager,
+            Plugin {
+                path: protected.clone(),
+                ..Plugin::test("CLA-76", "Waves", PluginType::VST3)
+            },
+        );
+        manager
+            .selected_plugins
+            .insert(dir.path().join("Gone.vst3"));
+
+        let plan = manager.removal_plan();
+        assert_eq!(kinds(&plan), [WarningKind::Protected, WarningKind::Missing]);
+        assert_eq!(
+            plan.warnings[0].message,
+            "CLA-76 is protected and will be skipped"
+        );
+        assert!(plan.items.is_empty());
+        assert_eq!((plan.file_count, plan.total_size), (0, 0));
+    }
+
+    #[test]
+    fn apple_plugins_are_system_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let delay = dir.path().join("AUDelay.component");
+        std::fs::write(&delay, [0; 8]).unwrap();
+
+        let mut manager = PluginManager::test();
+        add_plugin(
+            &mut manager,
+            Plugin {
+                path: delay,
+                bundle_id: Some("com.apple.audio.AUDelay".to_string()),
+                ..Plugin::test("AUDelay", "Apple", PluginType::AU)
+            },
+        );
+
+        let plan = manager.removal_plan();
+        assert_eq!(kinds(&plan), [WarningKind::SystemPath]);
+        assert_eq!(plan.count(ItemKind::Plugin), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_only_folders_need_admin() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Root can write anywhere, so there's nothing to check.
+        if unsafe { libc::getuid() } == 0 {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let locked = dir.path().join("Locked");
+        std::fs::create_dir(&locked).unwrap();
+        std::fs::write(locked.join("Synth.clap"), "").unwrap();
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o555)).unwrap();
+
+        let mut manager = PluginManager::test();
+        manager.selected_plugins.insert(locked.join("Synth.clap"));
+        let plan = manager.removal_plan();
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert_eq!(kinds(&plan), [WarningKind::NeedsAdmin]);
+        assert_eq!(plan.count(ItemKind::Other), 1);
+    }
+}
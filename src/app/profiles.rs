@@ -0,0 +1,172 @@
+use super::state::PluginManager;
+use crate::plugin::{Plugin, Profile};
+
+/// What switching to a profile would move, shown before anything is touched.
+#[derive(Debug, Clone)]
+pub struct ProfileSwitch {
+    pub name: String,
+    pub to_disable: Vec<Plugin>,
+    pub to_enable: Vec<Plugin>,
+    /// Protected plugins that would have to move but are left alone.
+    pub skipped: Vec<Plugin>,
+}
+
+impl ProfileSwitch {
+    pub fn is_empty(&self) -> bool {
+        self.to_disable.is_empty() && self.to_enable.is_empty()
+    }
+}
+
+impl PluginManager {
+    /// Saves the selected plugins as a profile, replacing any profile of that name.
+    pub fn save_selection_as_profile(&mut self, name: &str) {
+        let profile = Profile::from_plugins(
+            self.plugins
+                .values()
+                .flatten()
+                .filter(|p| self.selected_plugins.contains(&p.path)),
+        );
+        self.profiles.profiles.insert(name.to_string(), profile);
+        self.save_profiles();
+    }
+
+    /// Selects a profile's plugins, so it can be adjusted and saved again.
+    pub fn select_profile_plugins(&mut self, name: &str) {
+        let Some(profile) = self.profiles.profiles.get(name) else {
+            return;
+        };
+        self.selected_plugins = self
+            .plugins
+            .values()
+            .flatten()
+            .filter(|p| profile.contains(p))
+            .map(|p| p.path.clone())
+            .collect();
+        self.refresh_manufacturer_selection();
+    }
+
+    pub fn delete_profile(&mut self, name: &str) {
+        self.profiles.profiles.remove(name);
+        if self.profiles.active.as_deref() == Some(name) {
+            self.profiles.active = None;
+        }
+        self.save_profiles();
+    }
+
+    pub fn preview_profile_switch(&mut self, name: &str) {
+        let Some(profile) = self.profiles.profiles.get(name) else {
+            return;
+        };
+
+        let mut switch = ProfileSwitch {
+            name: name.to_string(),
+            to_disable: Vec::new(),
+            to_enable: Vec::new(),
+            skipped: Vec::new(),
+        };
+        for plugin in self.plugins.values().flatten() {
+            let wanted = profile.contains(plugin);
+            if wanted != plugin.disabled {
+                continue;
+            }
+            if self.is_protected(plugin) {
+                switch.skipped.push(plugin.clone());
+            } else if wanted {
+                switch.to_enable.push(plugin.clone());
+            } else {
+                switch.to_disable.push(plugin.clone());
+            }
+        }
+        self.profile_switch = Some(switch);
+    }
+
+    /// Moves everything in the previewed switch as one journaled batch, so a single
+    /// undo reverses the whole switch.
+    pub fn apply_profile_switch(&mut self) {
+        let Some(switch) = self.profile_switch.take() else {
+            return;
+        };
+
+        let changes: Vec<(Plugin, bool)> = switch
+            .to_disable
+            .into_iter()
+            .map(|p| (p, true))
+            .chain(switch.to_enable.into_iter().map(|p| (p, false)))
+            .collect();
+        let errors = self.set_disabled(&changes);
+        // A partial switch matches neither the old profile nor the new one.
+        if errors.is_empty() {
+            self.profiles.active = Some(switch.name);
+        } else {
+            self.profiles.active = None;
+            self.move_error = Some(format!(
+                "Only partly switched to {}; switch again to retry the rest.\n{}",
+                switch.name,
+                errors.join("\n")
+            ));
+        }
+        self.save_profiles();
+    }
+
+    fn save_profiles(&self) {
+        if let Err(e) = self.profiles.save() {
+            eprintln!("Error saving profiles: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::PluginType;
+
+    fn names(plugins: &[Plugin]) -> Vec<&str> {
+        plugins.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn preview_lists_only_plugins_that_change() {
+        let mut manager = PluginManager::test();
+        let mixing = [
+            Plugin::test("Pro-Q 3", "FabFilter", PluginType::VST3),
+            Plugin {
+                disabled: true,
+                ..Plugin::test("Pro-C 2", "FabFilter", PluginType::VST3)
+            },
+        ];
+        manager
+            .profiles
+            .profiles
+            .insert("Mixing".to_string(), Profile::from_plugins(&mixing));
+        manager.plugins.insert(
+            "FabFilter".to_string(),
+            vec![
+                mixing[0].clone(),
+                mixing[1].clone(),
+                Plugin::test("Saturn 2", "FabFilter", PluginType::VST3),
+                Plugin {
+                    disabled: true,
+                    ..Plugin::test("Timeless 3", "FabFilter", PluginType::VST3)
+                },
+            ],
+        );
+        manager.plugins.insert(
+            "Waves".to_string(),
+            vec![Plugin::test("CLA-76", "Waves", PluginType::VST3)],
+        );
+        manager.protect_list.vendors.insert("Waves".to_string());
+
+        manager.preview_profile_switch("Mixing");
+        let switch = manager.profile_switch.unwrap();
+        assert_eq!(names(&switch.to_enable), ["Pro-C 2"]);
+        assert_eq!(names(&switch.to_disable), ["Saturn 2"]);
+        assert_eq!(names(&switch.skipped), ["CLA-76"]);
+    }
+
+    #[test]
+    fn unknown_profiles_have_no_preview() {
+        let mut manager = PluginManager::test();
+        manager.preview_profile_switch("Missing");
+        assert!(manager.profile_switch.is_none());
+    }
+}
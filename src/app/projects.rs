@@ -0,0 +1,77 @@
+use super::state::PluginManager;
+use crate::scanner::{project_usage, scan_projects, Project};
+use eframe::egui;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, TryRecvError};
+use std::time::Duration;
+
+impl PluginManager {
+    pub fn choose_project_folder(&mut self) {
+        if let Some(folder) = rfd::FileDialog::new()
+            .set_title("Choose a folder of Reaper or Ableton projects")
+            .pick_folder()
+        {
+            self.set_project_folder(folder);
+        }
+    }
+
+    /// Starts reading the projects in `folder` on another thread, since a large folder
+    /// of sets takes a while to decompress. `poll_project_scan` picks up the result.
+    pub fn set_project_folder(&mut self, folder: PathBuf) {
+        let (sender, receiver) = mpsc::channel();
+        let scan_folder = folder.clone();
+        std::thread::spawn(move || {
+            let _ = sender.send(scan_projects(&scan_folder));
+        });
+        self.project_scan = Some((folder, receiver));
+    }
+
+    /// Blocks until the project scan started by `set_project_folder` finishes, for the
+    /// command line, which has no frames to poll from.
+    pub fn wait_for_project_scan(&mut self) {
+        if let Some((folder, receiver)) = self.project_scan.take() {
+            if let Ok(projects) = receiver.recv() {
+                self.finish_project_scan(folder, projects);
+            }
+        }
+    }
+
+    /// Applies a finished project scan. Keeps the UI repainting while one is still
+    /// running.
+    pub(super) fn poll_project_scan(&mut self, ctx: &egui::Context) {
+        let Some((_, receiver)) = &self.project_scan else {
+            return;
+        };
+        let projects = match receiver.try_recv() {
+            Ok(projects) => projects,
+            Err(TryRecvError::Empty) => {
+                ctx.request_repaint_after(Duration::from_millis(100));
+                return;
+            }
+            Err(TryRecvError::Disconnected) => {
+                self.project_scan = None;
+                return;
+            }
+        };
+        if let Some((folder, _)) = self.project_scan.take() {
+            self.finish_project_scan(folder, projects);
+        }
+    }
+
+    fn finish_project_scan(&mut self, folder: PathBuf, projects: Vec<Project>) {
+        self.projects = projects;
+        self.project_folder = Some(folder);
+        self.refresh_project_usage();
+    }
+
+    pub(super) fn refresh_project_usage(&mut self) {
+        self.project_usage = self
+            .plugins
+            .values()
+            .flatten()
+            .filter_map(|plugin| {
+                project_usage(&self.projects, plugin).map(|usage| (plugin.path.clone(), usage))
+            })
+            .collect();
+    }
+}
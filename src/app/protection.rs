@@ -0,0 +1,156 @@
+use super::state::PluginManager;
+use crate::plugin::{Plugin, ProtectList};
+use std::path::Path;
+
+impl PluginManager {
+    /// Whether `plugin` is on the protect list and hasn't been unlocked this session.
+    /// Everything is protected while the list can't be read.
+    pub fn is_protected(&self, plugin: &Plugin) -> bool {
+        self.protect_list_error.is_some()
+            || (!self.unlocked.contains(&plugin.path) && self.protect_list.protects(plugin))
+    }
+
+    /// Like `is_protected`, for any path about to be removed or moved: a protected
+    /// plugin, a folder holding one or a file inside one. Other paths are matched
+    /// against the path globs.
+    pub fn is_protected_path(&self, path: &Path) -> bool {
+        if self.protect_list_error.is_some() {
+            return true;
+        }
+        let overlaps = |p: &Plugin| p.path.starts_with(path) || path.starts_with(&p.path);
+        if self
+            .plugins
+            .values()
+            .flatten()
+            .any(|p| overlaps(p) && self.is_protected(p))
+        {
+            return true;
+        }
+        !self.unlocked.contains(path) && self.protect_list.protects_path(path)
+    }
+
+    /// Lets a protected plugin be selected and removed until the next launch, or locks
+    /// it again.
+    pub fn toggle_unlock(&mut self, plugin: &Plugin) {
+        if !self.unlocked.remove(&plugin.path) {
+            self.unlocked.insert(plugin.path.clone());
+        } else {
+            self.selected_plugins.remove(&plugin.path);
+            self.refresh_manufacturer_selection();
+        }
+    }
+
+    pub fn protect_plugin(&mut self, plugin: &Plugin) {
+        self.protect_list.protect_plugin(plugin);
+        self.unlocked.remove(&plugin.path);
+        self.save_protect_list();
+    }
+
+    pub fn unprotect_plugin(&mut self, plugin: &Plugin) {
+        self.protect_list.unprotect_plugin(plugin);
+        self.save_protect_list();
+    }
+
+    pub fn protect_vendor(&mut self, manufacturer: &str) {
+        self.protect_list.vendors.insert(manufacturer.to_string());
+        self.save_protect_list();
+    }
+
+    pub fn unprotect_vendor(&mut self, manufacturer: &str) {
+        self.protect_list.vendors.remove(manufacturer);
+        self.save_protect_list();
+    }
+
+    /// Tries reading the protect list again after it failed to load.
+    pub fn reload_protect_list(&mut self) {
+        match ProtectList::load() {
+            Ok(list) => {
+                self.protect_list = list;
+                self.protect_list_error = None;
+            }
+            Err(e) => self.protect_list_error = Some(format!("{:#}", e)),
+        }
+        self.save_protect_list();
+    }
+
+    /// Saves the protect list and drops anything it now covers from the selection. A
+    /// list that failed to load is never saved, so the file on disk isn't replaced.
+    pub fn save_protect_list(&mut self) {
+        self.protect_list.compile_globs();
+        if self.protect_list_error.is_none() {
+            if let Err(e) = self.protect_list.save() {
+                eprintln!("Error saving protect list: {}", e);
+            }
+        }
+        let protected: Vec<_> = self
+            .plugins
+            .values()
+            .flatten()
+            .filter(|p| self.is_protected(p))
+            .map(|p| p.path.clone())
+            .collect();
+        for path in protected {
+            self.selected_plugins.remove(&path);
+        }
+        self.refresh_manufacturer_selection();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::PluginType;
+
+    fn manager() -> PluginManager {
+        let mut manager = PluginManager::test();
+        let synth = Plugin {
+            path: "/vst3/Waves/Synth.vst3".into(),
+            ..Plugin::test("Synth", "Waves", PluginType::VST3)
+        };
+        manager.plugins.insert("Waves".to_string(), vec![synth]);
+        manager.protect_list.vendors.insert("Waves".to_string());
+        manager
+    }
+
+    #[test]
+    fn protects_folders_around_and_files_inside_plugins() {
+        let manager = manager();
+        for path in [
+            "/vst3/Waves/Synth.vst3",
+            "/vst3/Waves",
+            "/vst3",
+            "/vst3/Waves/Synth.vst3/Contents/Info.plist",
+        ] {
+            assert!(manager.is_protected_path(Path::new(path)), "{}", path);
+        }
+        for path in ["/vst3/Waves Extra", "/vst3/Other/Synth.vst3", "/vst2"] {
+            assert!(!manager.is_protected_path(Path::new(path)), "{}", path);
+        }
+    }
+
+    #[test]
+    fn unlocked_plugins_no_longer_protect_their_folder() {
+        let mut manager = manager();
+        manager.unlocked.insert("/vst3/Waves/Synth.vst3".into());
+        assert!(!manager.is_protected_path(Path::new("/vst3/Waves")));
+    }
+
+    #[test]
+    fn path_globs_cover_paths_that_are_not_plugins() {
+        let mut manager = manager();
+        manager
+            .protect_list
+            .path_globs
+            .insert("/presets/**".to_string());
+        manager.protect_list.compile_globs();
+        assert!(manager.is_protected_path(Path::new("/presets/Waves/Bass.fxp")));
+        assert!(!manager.is_protected_path(Path::new("/presets")));
+    }
+
+    #[test]
+    fn everything_is_protected_while_the_list_is_unreadable() {
+        let mut manager = manager();
+        manager.protect_list_error = Some("Error reading protected.json".to_string());
+        assert!(manager.is_protected_path(Path::new("/vst2/Anything.dll")));
+    }
+}
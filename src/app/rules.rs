@@ -0,0 +1,89 @@
+use super::state::PluginManager;
+use crate::plugin::{plugin_identity, Rule, RuleContext};
+use crate::utils::error::Result;
+use std::path::PathBuf;
+
+impl PluginManager {
+    /// Selects every visible, unprotected plugin matching `rule`, replacing the current
+    /// selection or adding to it. Returns how many plugins matched.
+    pub fn select_by_rule(&mut self, rule: &Rule, replace: bool) -> usize {
+        let context = RuleContext::new(self.plugins.values().flatten());
+        let matching: Vec<PathBuf> = self
+            .plugins
+            .values()
+            .flatten()
+            .filter(|p| self.is_visible(p) && !self.is_protected(p))
+            .filter(|p| rule.matches(p, &context))
+            .map(|p| p.path.clone())
+            .collect();
+
+        if replace {
+            self.selected_plugins.clear();
+        }
+        let count = matching.len();
+        self.selected_plugins.extend(matching);
+        self.refresh_manufacturer_selection();
+        count
+    }
+
+    /// Parses the rule typed into the selection window and applies it.
+    pub fn apply_rule_text(&mut self, replace: bool) -> Result<usize> {
+        let rule = Rule::parse(&self.rule_text)?;
+        Ok(self.select_by_rule(&rule, replace))
+    }
+
+    /// Selects every visible, unprotected plugin that isn't selected and deselects the
+    /// rest.
+    pub fn invert_selection(&mut self) {
+        let inverted: Vec<PathBuf> = self
+            .plugins
+            .values()
+            .flatten()
+            .filter(|p| self.is_visible(p) && !self.is_protected(p))
+            .filter(|p| !self.selected_plugins.contains(&p.path))
+            .map(|p| p.path.clone())
+            .collect();
+        self.selected_plugins = inverted.into_iter().collect();
+        self.refresh_manufacturer_selection();
+    }
+
+    pub fn save_selection(&mut self, name: &str) {
+        let identities = self
+            .plugins
+            .values()
+            .flatten()
+            .filter(|p| self.selected_plugins.contains(&p.path))
+            .map(plugin_identity)
+            .collect();
+        self.saved_selections
+            .selections
+            .insert(name.to_string(), identities);
+        self.save_saved_selections();
+    }
+
+    /// Replaces the selection with a saved one, leaving out anything since protected.
+    pub fn load_selection(&mut self, name: &str) {
+        let Some(identities) = self.saved_selections.selections.get(name) else {
+            return;
+        };
+        self.selected_plugins = self
+            .plugins
+            .values()
+            .flatten()
+            .filter(|p| !self.is_protected(p) && identities.contains(&plugin_identity(p)))
+            .map(|p| p.path.clone())
+            .collect();
+        self.refresh_manufacturer_selection();
+    }
+
+    pub fn delete_saved_selection(&mut self, name: &str) {
+        self.saved_selections.selections.remove(name);
+        self.save_saved_selections();
+    }
+
+    fn save_saved_selections(&self) {
+        if let Err(e) = self.saved_selections.save() {
+            eprintln!("Error saving selections: {}", e);
+        }
+    }
+}
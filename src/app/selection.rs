@@ -1,27 +1,47 @@
+use super::journal::{JournalEntry, Operation, OperationKind};
 use super::state::PluginManager;
-use crate::plugin::Plugin;
+use crate::plugin::{find_duplicates, Plugin};
+use crate::scanner::{
+    clear_daw_caches, find_leftovers, find_uninstaller, launch_uninstaller, read_daw_caches,
+    CacheBackup, InstalledPackage, ReceiptIndex,
+};
+use crate::utils::elevation::elevated_trash;
+use crate::utils::fs::{locate_in_trash, move_to_trash, TrashOutcome, TrashResult};
 use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 impl PluginManager {
+    /// Selects or deselects a vendor's plugins. Only plugins shown by the current
+    /// filters are touched, so a filtered list never selects what it hides, and
+    /// protected plugins are always left out.
     pub fn toggle_manufacturer(&mut self, manufacturer: &str) {
+        let paths: Vec<_> = self
+            .plugins
+            .get(manufacturer)
+            .into_iter()
+            .flatten()
+            .filter(|p| self.is_visible(p) && !self.is_protected(p))
+            .map(|p| p.path.clone())
+            .collect();
+
         if self.selected_manufacturers.contains(manufacturer) {
             self.selected_manufacturers.remove(manufacturer);
-            if let Some(plugins) = self.plugins.get(manufacturer) {
-                for plugin in plugins {
-                    self.selected_plugins.remove(&plugin.path);
-                }
+            for path in paths {
+                self.selected_plugins.remove(&path);
             }
         } else {
             self.selected_manufacturers.insert(manufacturer.to_string());
-            if let Some(plugins) = self.plugins.get(manufacturer) {
-                for plugin in plugins {
-                    self.selected_plugins.insert(plugin.path.clone());
-                }
+            for path in paths {
+                self.selected_plugins.insert(path);
             }
         }
     }
 
     pub fn toggle_plugin(&mut self, plugin: &Plugin) {
+        if self.is_protected(plugin) {
+            return;
+        }
         if self.selected_plugins.contains(&plugin.path) {
             self.selected_plugins.remove(&plugin.path);
         } else {
@@ -29,8 +49,7 @@ impl PluginManager {
         }
 
         if let Some(plugins) = self.plugins.get(&plugin.manufacturer) {
-            let all_selected = plugins.iter().all(|p| self.selected_plugins.contains(&p.path));
-            if all_selected {
+            if self.all_selectable_selected(plugins) {
                 self.selected_manufacturers.insert(plugin.manufacturer.clone());
             } else {
                 self.selected_manufacturers.remove(&plugin.manufacturer);
@@ -38,38 +57,252 @@ impl PluginManager {
         }
     }
 
-    pub fn delete_selected_plugins(&mut self) {
-        if self.selected_plugins.is_empty() {
-            return;
+    /// Roughly how much space moving the current selection to the bin would free.
+    pub fn selected_size(&self) -> u64 {
+        let plugins: u64 = self
+            .plugins
+            .values()
+            .flatten()
+            .filter(|p| self.selected_plugins.contains(&p.path))
+            .map(|p| p.size)
+            .sum();
+        let leftovers: u64 = self
+            .leftovers
+            .values()
+            .flatten()
+            .filter(|l| self.selected_leftovers.contains(&l.path))
+            .map(|l| l.size)
+            .sum();
+        plugins + leftovers
+    }
+
+    pub fn find_duplicate_plugins(&mut self) {
+        self.duplicates = find_duplicates(self.plugins.values().flatten());
+        self.show_duplicates = true;
+    }
+
+    pub fn select_redundant_duplicates(&mut self) {
+        let redundant: Vec<_> = self
+            .duplicates
+            .iter()
+            .flat_map(|set| set.redundant_paths())
+            .filter(|path| !self.is_protected_path(path))
+            .collect();
+        self.selected_plugins.extend(redundant);
+        self.refresh_manufacturer_selection();
+    }
+
+    /// Selects every plugin a DAW has marked as failed or blocklisted.
+    pub fn select_flagged_plugins(&mut self) {
+        let flagged: Vec<_> = self
+            .plugins
+            .values()
+            .flatten()
+            .filter(|p| !self.daw_flags(p).is_empty() && !self.is_protected(p))
+            .map(|p| p.path.clone())
+            .collect();
+        self.selected_plugins.extend(flagged);
+        self.refresh_manufacturer_selection();
+    }
+
+    pub fn find_vendor_leftovers(&mut self, manufacturer: &str) {
+        let plugin_dirs = self.scanner.plugin_directories();
+        let found = find_leftovers(manufacturer, &plugin_dirs);
+        self.leftovers.insert(manufacturer.to_string(), found);
+        self.leftovers_vendor = Some(manufacturer.to_string());
+    }
+
+    pub fn toggle_leftover(&mut self, path: &Path) {
+        if !self.selected_leftovers.remove(path) {
+            self.selected_leftovers.insert(path.to_path_buf());
         }
+    }
 
-        let paths_to_delete: Vec<_> = self.selected_plugins.iter().cloned().collect();
-        let mut actually_deleted_paths = HashSet::new();
-        let mut deletion_failed = false;
+    pub fn show_plugin_details(&mut self, plugin: &Plugin) {
+        if self.receipts.is_none() {
+            self.receipts = Some(ReceiptIndex::load());
+        }
+        let entries = self
+            .uninstall_entries
+            .get_or_insert_with(|| self.uninstall_registry.entries());
+        let plugin_dirs = self.scanner.plugin_directories();
 
-        let result = trash::delete_all(&paths_to_delete);
+        self.details_uninstaller = find_uninstaller(entries, plugin, &plugin_dirs).cloned();
+        self.uninstaller_error = None;
+        self.details_plugin = Some(plugin.clone());
+    }
 
-        for path in &paths_to_delete {
-            if !path.exists() {
-                actually_deleted_paths.insert(path.clone());
+    pub fn run_details_uninstaller(&mut self) {
+        if let Some(entry) = &self.details_uninstaller {
+            if let Err(e) = launch_uninstaller(entry) {
+                self.uninstaller_error = Some(e.to_string());
             }
         }
+    }
 
-        if result.is_err() || actually_deleted_paths.len() < paths_to_delete.len() {
-            if let Err(e) = result {
-                eprintln!("Error moving plugins to trash: {}", e);
+    /// Selects every bundle and file the package installed, and remembers to forget its
+    /// receipt once they have all been moved to the bin.
+    pub fn uninstall_package(&mut self, package: &InstalledPackage) {
+        let Some(receipts) = &self.receipts else {
+            return;
+        };
+        self.selected_plugins.extend(
+            receipts
+                .removal_paths(package)
+                .into_iter()
+                .filter(|p| p.exists()),
+        );
+        self.packages_to_forget.push(package.clone());
+        self.refresh_manufacturer_selection();
+        self.show_confirmation = true;
+    }
+
+    /// Moves the receipts of packages whose files are all gone to the bin, so `pkgutil`
+    /// stops listing them. Receipts live in a system folder, so any the user can't move
+    /// go through the administrator prompt. Returns the journal entries for them, so
+    /// undoing the removal puts the receipts back along with the files.
+    fn forget_uninstalled_packages(&mut self) -> Vec<JournalEntry> {
+        let receipts = &self.receipts;
+        let mut uninstalled = Vec::new();
+        self.packages_to_forget.retain(|package| {
+            let remaining = receipts
+                .as_ref()
+                .is_some_and(|r| r.removal_paths(package).iter().any(|p| p.exists()));
+            if !remaining {
+                uninstalled.push(package.receipt.clone());
             }
-            deletion_failed = true;
+            remaining
+        });
+
+        let files: Vec<PathBuf> = uninstalled
+            .iter()
+            .flat_map(|receipt| receipt.files())
+            .filter(|path| path.exists())
+            .collect();
+        let started = SystemTime::now();
+        let mut results = move_to_trash(&files);
+        let denied: Vec<PathBuf> = results
+            .iter()
+            .filter(|r| r.outcome == TrashOutcome::PermissionDenied)
+            .map(|r| r.path.clone())
+            .collect();
+        if let (false, Some(elevator)) = (denied.is_empty(), self.elevator.clone()) {
+            results.retain(|r| r.outcome != TrashOutcome::PermissionDenied);
+            results.extend(elevated_trash(elevator.as_ref(), &denied));
         }
 
-        if deletion_failed {
-            self.deletion_error = Some(
-                "Some or all plugins could not be moved to bin.\nThis can happen on Windows due to file permissions.\nPlease try running this application as an administrator.".to_string()
-            );
+        let moved: HashSet<PathBuf> = results
+            .iter()
+            .filter(|r| r.outcome == TrashOutcome::Moved)
+            .map(|r| r.path.clone())
+            .collect();
+        let failed: Vec<String> = results
+            .iter()
+            .filter(|r| !matches!(r.outcome, TrashOutcome::Moved | TrashOutcome::NotFound))
+            .map(|r| format!("{}: {}", r.path.display(), r.outcome.label()))
+            .collect();
+
+        let forgotten: Vec<&str> = uninstalled
+            .iter()
+            .filter(|receipt| receipt.files().iter().all(|path| !path.exists()))
+            .map(|receipt| receipt.package_id.as_str())
+            .collect();
+        if let Some(receipts) = &mut self.receipts {
+            receipts
+                .packages
+                .retain(|p| !forgotten.contains(&p.receipt.package_id.as_str()));
+        }
+
+        if !failed.is_empty() {
+            self.deletion_error = Some(format!(
+                "The package files were moved to the bin, but these installer receipts could not be:\n{}",
+                failed.join("\n")
+            ));
+        }
+        self.journal_entries(&files, &moved, started)
+    }
+
+    pub(super) fn refresh_manufacturer_selection(&mut self) {
+        self.selected_manufacturers = self
+            .plugins
+            .iter()
+            .filter(|(_, plugins)| self.all_selectable_selected(plugins))
+            .map(|(manufacturer, _)| manufacturer.clone())
+            .collect();
+    }
+
+    /// Whether a vendor counts as selected: it has plugins that can be selected and all
+    /// of them are. Protected plugins can't be, so they're left out.
+    fn all_selectable_selected(&self, plugins: &[Plugin]) -> bool {
+        let mut selectable = plugins.iter().filter(|p| !self.is_protected(p)).peekable();
+        selectable.peek().is_some() && selectable.all(|p| self.selected_plugins.contains(&p.path))
+    }
+
+    pub fn delete_selected_plugins(&mut self) {
+        self.remove_selected_with(move_to_trash);
+    }
+
+    /// Moves the selection to the bin with `remove`, then journals what went and drops
+    /// it from the lists.
+    fn remove_selected_with(&mut self, remove: impl FnOnce(&[PathBuf]) -> Vec<TrashResult>) {
+        if self.selected_plugins.is_empty() && self.selected_leftovers.is_empty() {
+            return;
+        }
+        if let Some(error) = &self.protect_list_error {
+            self.deletion_error = Some(format!(
+                "Nothing was moved, because the protect list couldn't be read:\n{}",
+                error
+            ));
+            return;
+        }
+
+        let (protected, paths_to_delete): (Vec<_>, Vec<_>) = self
+            .selected_plugins
+            .iter()
+            .chain(self.selected_leftovers.iter())
+            .cloned()
+            .partition(|path| self.is_protected_path(path));
+        let started = SystemTime::now();
+        let mut results = if paths_to_delete.is_empty() {
+            Vec::new()
+        } else {
+            remove(&paths_to_delete)
+        };
+        results.extend(protected.into_iter().map(|path| TrashResult {
+            path,
+            outcome: TrashOutcome::Protected,
+        }));
+        let actually_deleted_paths: HashSet<PathBuf> = results
+            .iter()
+            .filter(|r| r.outcome == TrashOutcome::Moved)
+            .map(|r| r.path.clone())
+            .collect();
+        if actually_deleted_paths.len() < results.len() {
+            self.trash_results = Some(results);
         }
 
+        let mut entries = self.journal_entries(&paths_to_delete, &actually_deleted_paths, started);
+        if !self.packages_to_forget.is_empty() {
+            entries.extend(self.forget_uninstalled_packages());
+        }
+        let mut operation = Operation::new(OperationKind::Trash, entries);
+        if self.clear_daw_caches {
+            let removed_plugins: Vec<_> = operation
+                .entries
+                .iter()
+                .filter(|e| e.plugin.is_some())
+                .map(|e| e.original.clone())
+                .collect();
+            operation.cache_backup = self.clear_removed_from_daw_caches(&removed_plugins);
+        }
+        self.record_operation(operation);
+
         if !actually_deleted_paths.is_empty() {
             self.selected_plugins.retain(|p| !actually_deleted_paths.contains(p));
+            self.selected_leftovers.retain(|p| !actually_deleted_paths.contains(p));
+            for leftovers in self.leftovers.values_mut() {
+                leftovers.retain(|l| !actually_deleted_paths.contains(&l.path));
+            }
 
             let mut affected_manufacturers = HashSet::new();
             self.plugins.retain(|manufacturer, plugins| {
@@ -85,6 +318,150 @@ impl PluginManager {
             for m_name in affected_manufacturers {
                 self.selected_manufacturers.remove(&m_name);
             }
+
+            if !self.duplicates.is_empty() {
+                self.duplicates = find_duplicates(self.plugins.values().flatten());
+            }
+        }
+    }
+
+    /// Selects only the items the last move to the bin failed on and tries them again.
+    pub fn retry_failed_trash(&mut self) {
+        let Some(results) = self.trash_results.take() else {
+            return;
+        };
+        let failed: HashSet<PathBuf> = results
+            .into_iter()
+            .filter(|r| {
+                !matches!(
+                    r.outcome,
+                    TrashOutcome::Moved | TrashOutcome::NotFound | TrashOutcome::Protected
+                )
+            })
+            .map(|r| r.path)
+            .collect();
+
+        self.selected_plugins.retain(|p| failed.contains(p));
+        self.selected_leftovers.retain(|p| failed.contains(p));
+        self.refresh_manufacturer_selection();
+        self.delete_selected_plugins();
+    }
+
+    /// Tries the items that failed for lack of permission again through the system's
+    /// administrator prompt.
+    pub fn retry_failed_as_admin(&mut self) {
+        let (Some(results), Some(elevator)) = (self.trash_results.take(), self.elevator.clone())
+        else {
+            return;
+        };
+        let denied: HashSet<PathBuf> = results
+            .into_iter()
+            .filter(|r| r.outcome == TrashOutcome::PermissionDenied)
+            .map(|r| r.path)
+            .collect();
+
+        self.selected_plugins.retain(|p| denied.contains(p));
+        self.selected_leftovers.retain(|p| denied.contains(p));
+        self.refresh_manufacturer_selection();
+        self.remove_selected_with(|paths| elevated_trash(elevator.as_ref(), paths));
+    }
+
+    /// A plain-text summary of the last move to the bin, for pasting into a bug report.
+    pub fn trash_report(&self) -> String {
+        self.trash_results
+            .iter()
+            .flatten()
+            .map(|r| format!("{}: {}", r.path.display(), r.outcome.label()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// What the journal needs to know about the paths that were actually removed.
+    fn journal_entries(
+        &self,
+        paths: &[PathBuf],
+        removed: &HashSet<PathBuf>,
+        since: SystemTime,
+    ) -> Vec<JournalEntry> {
+        let removed: Vec<PathBuf> = paths
+            .iter()
+            .filter(|path| removed.contains(*path))
+            .cloned()
+            .collect();
+        let binned = locate_in_trash(&removed, since);
+        removed
+            .iter()
+            .zip(binned)
+            .map(|(path, moved_to)| {
+                let plugin = self.plugins.values().flatten().find(|p| &p.path == path);
+                let leftover = self.leftovers.values().flatten().find(|l| &l.path == path);
+                JournalEntry {
+                    original: path.clone(),
+                    moved_to,
+                    size: plugin
+                        .map(|p| p.size)
+                        .or_else(|| leftover.map(|l| l.size))
+                        .unwrap_or_default(),
+                    plugin: plugin.cloned(),
+                }
+            })
+            .collect()
+    }
+
+    /// Removes deleted plugins from DAW caches, returning the backup of what changed.
+    fn clear_removed_from_daw_caches(&mut self, removed: &[PathBuf]) -> Option<CacheBackup> {
+        if removed.is_empty() {
+            return None;
+        }
+        let mut backup = match CacheBackup::create() {
+            Ok(backup) => backup,
+            Err(e) => {
+                eprintln!("Not clearing DAW caches: {}", e);
+                return None;
+            }
+        };
+
+        let errors = clear_daw_caches(&self.daw_cleaners, removed, &mut backup);
+        if !errors.is_empty() {
+            let message = format!(
+                "Some DAW caches could not be cleared:\n{}",
+                errors.join("\n")
+            );
+            self.deletion_error = Some(match self.deletion_error.take() {
+                Some(existing) => format!("{}\n\n{}", existing, message),
+                None => message,
+            });
         }
+
+        if backup.is_empty() {
+            return None;
+        }
+        self.daw_cache_entries = read_daw_caches(&self.daw_readers);
+        Some(backup)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::PluginType;
+
+    #[test]
+    fn vendor_selection_ignores_protected_plugins_either_way() {
+        let mut manager = PluginManager::test();
+        let protected = Plugin::test("CLA-76", "Waves", PluginType::VST3);
+        let free = Plugin::test("H-Reverb", "Waves", PluginType::VST3);
+        manager
+            .plugins
+            .insert("Waves".to_string(), vec![protected.clone(), free.clone()]);
+        manager.protect_list.protect_plugin(&protected);
+
+        manager.toggle_plugin(&free);
+        assert!(manager.selected_manufacturers.contains("Waves"));
+        manager.refresh_manufacturer_selection();
+        assert!(manager.selected_manufacturers.contains("Waves"));
+
+        manager.toggle_plugin(&free);
+        assert!(manager.selected_manufacturers.is_empty());
     }
 }
@@ -1,28 +1,205 @@
-use crate::plugin::{clean_manufacturer_name, Plugin};
-use crate::scanner::PluginScanner;
-use std::collections::{BTreeMap, HashSet};
+use super::journal::Journal;
+use super::plan::RemovalPlan;
+use super::profiles::ProfileSwitch;
+use crate::plugin::{
+    clean_manufacturer_name, infer_manufacturer, DuplicateSet, FormatPolicy, ManufacturerSource,
+    Plugin, Profiles, ProtectList, RedundantFormat, SavedSelections, UpdateFeed, UpdateManifest,
+    VendorAliases, VendorOverrides,
+};
+use crate::scanner::{
+    read_daw_caches, system_cleaners, system_detectors, system_readers, system_registry,
+    DawCacheCleaner, DawCacheEntry, DawCacheReader, InstalledPackage, Leftover, LicenceDetector,
+    LicenceFootprint, PluginScanner, Project, ProjectUsage, ReceiptIndex, UninstallEntry,
+    UninstallRegistry,
+};
+use crate::utils::elevation::{system_elevator, Elevator};
+use crate::utils::error::Result;
+use crate::utils::fs::TrashResult;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::mpsc::Receiver;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Name,
+    LargestFirst,
+    Version,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 3] = [SortOrder::Name, SortOrder::LargestFirst, SortOrder::Version];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortOrder::Name => "Name",
+            SortOrder::LargestFirst => "Largest first",
+            SortOrder::Version => "Oldest version first",
+        }
+    }
+}
 
 pub struct PluginManager {
     pub plugins: BTreeMap<String, Vec<Plugin>>,
     pub selected_plugins: HashSet<PathBuf>,
     pub selected_manufacturers: HashSet<String>,
     pub scanning: bool,
+    pub sort_order: SortOrder,
+    pub only_unsigned: bool,
     pub show_confirmation: bool,
+    pub pending_plan: Option<RemovalPlan>,
     pub scanner: PluginScanner,
+    pub vendor_aliases: VendorAliases,
+    pub vendor_overrides: VendorOverrides,
+    pub protect_list: ProtectList,
+    /// Why the protect list couldn't be loaded. While set, everything counts as
+    /// protected and the list is never saved.
+    pub protect_list_error: Option<String>,
+    pub unlocked: HashSet<PathBuf>,
+    pub show_protect_list: bool,
+    pub new_protect_glob: String,
+    pub profiles: Profiles,
+    pub profile_switch: Option<ProfileSwitch>,
+    pub new_profile_name: Option<String>,
+    pub show_rule_window: bool,
+    pub rule_text: String,
+    pub rule_message: Option<String>,
+    pub saved_selections: SavedSelections,
+    pub new_selection_name: String,
+    pub dragging_plugin: Option<Plugin>,
+    pub renaming_vendor: Option<(String, String)>,
     pub deletion_error: Option<String>,
+    pub trash_results: Option<Vec<TrashResult>>,
+    pub elevator: Option<Rc<dyn Elevator>>,
+    pub duplicates: Vec<DuplicateSet>,
+    pub show_duplicates: bool,
+    pub format_policy: FormatPolicy,
+    pub redundant_formats: Vec<RedundantFormat>,
+    pub show_format_cleanup: bool,
+    pub leftovers: BTreeMap<String, Vec<Leftover>>,
+    pub selected_leftovers: HashSet<PathBuf>,
+    pub leftovers_vendor: Option<String>,
+    pub details_plugin: Option<Plugin>,
+    pub receipts: Option<ReceiptIndex>,
+    pub packages_to_forget: Vec<InstalledPackage>,
+    pub uninstall_registry: Box<dyn UninstallRegistry>,
+    pub uninstall_entries: Option<Vec<UninstallEntry>>,
+    pub details_uninstaller: Option<UninstallEntry>,
+    pub uninstaller_error: Option<String>,
+    pub daw_readers: Vec<Box<dyn DawCacheReader>>,
+    pub daw_cache_entries: Vec<DawCacheEntry>,
+    pub daw_cleaners: Vec<Box<dyn DawCacheCleaner>>,
+    pub clear_daw_caches: bool,
+    pub licence_detectors: Vec<Box<dyn LicenceDetector>>,
+    /// How each vendor group's plugins are licensed, by group name.
+    pub licensing: HashMap<String, Vec<LicenceFootprint>>,
+    pub journal: Journal,
+    pub undo_error: Option<String>,
+    pub move_error: Option<String>,
+    pub project_folder: Option<PathBuf>,
+    pub projects: Vec<Project>,
+    /// The project folder being read in the background.
+    pub project_scan: Option<(PathBuf, Receiver<Vec<Project>>)>,
+    pub project_usage: HashMap<PathBuf, ProjectUsage>,
+    /// Plugins whose other formats are on different versions, with those versions.
+    pub version_mismatches: HashMap<PathBuf, String>,
+    pub update_feed: UpdateFeed,
+    pub update_manifest: Option<UpdateManifest>,
+    pub update_source: String,
+    pub update_error: Option<String>,
+    /// The manifest being fetched in the background, and where it comes from.
+    pub update_check: Option<(String, Receiver<Result<UpdateManifest>>)>,
+    pub show_update_feed: bool,
 }
 
 impl PluginManager {
     pub fn new() -> Self {
+        let update_feed = UpdateFeed::load();
+        let update_source = update_feed.source.clone().unwrap_or_default();
+        let (protect_list, protect_list_error) = match ProtectList::load() {
+            Ok(list) => (list, None),
+            Err(e) => (ProtectList::default(), Some(format!("{:#}", e))),
+        };
         Self {
             plugins: BTreeMap::new(),
             selected_plugins: HashSet::new(),
             selected_manufacturers: HashSet::new(),
             scanning: false,
+            sort_order: SortOrder::Name,
+            only_unsigned: false,
             show_confirmation: false,
+            pending_plan: None,
             scanner: PluginScanner::new(),
+            vendor_aliases: VendorAliases::load(),
+            vendor_overrides: VendorOverrides::load(),
+            protect_list,
+            protect_list_error,
+            unlocked: HashSet::new(),
+            show_protect_list: false,
+            new_protect_glob: String::new(),
+            profiles: Profiles::load(),
+            profile_switch: None,
+            new_profile_name: None,
+            show_rule_window: false,
+            rule_text: String::new(),
+            rule_message: None,
+            saved_selections: SavedSelections::load(),
+            new_selection_name: String::new(),
+            dragging_plugin: None,
+            renaming_vendor: None,
             deletion_error: None,
+            trash_results: None,
+            elevator: system_elevator().map(Rc::from),
+            duplicates: Vec::new(),
+            show_duplicates: false,
+            format_policy: FormatPolicy::load(),
+            redundant_formats: Vec::new(),
+            show_format_cleanup: false,
+            leftovers: BTreeMap::new(),
+            selected_leftovers: HashSet::new(),
+            leftovers_vendor: None,
+            details_plugin: None,
+            receipts: None,
+            packages_to_forget: Vec::new(),
+            uninstall_registry: system_registry(),
+            uninstall_entries: None,
+            details_uninstaller: None,
+            uninstaller_error: None,
+            daw_readers: system_readers(),
+            daw_cache_entries: Vec::new(),
+            daw_cleaners: system_cleaners(),
+            clear_daw_caches: false,
+            licence_detectors: system_detectors(),
+            licensing: HashMap::new(),
+            journal: Journal::load(),
+            undo_error: None,
+            move_error: None,
+            project_folder: None,
+            projects: Vec::new(),
+            project_scan: None,
+            project_usage: HashMap::new(),
+            version_mismatches: HashMap::new(),
+            update_feed,
+            update_manifest: None,
+            update_source,
+            update_error: None,
+            update_check: None,
+            show_update_feed: false,
+        }
+    }
+
+    /// A manager with empty settings, whatever the user running the tests has saved.
+    #[cfg(test)]
+    pub fn test() -> Self {
+        Self {
+            vendor_overrides: VendorOverrides::default(),
+            protect_list: ProtectList::default(),
+            protect_list_error: None,
+            profiles: Profiles::default(),
+            saved_selections: SavedSelections::default(),
+            format_policy: FormatPolicy::default(),
+            journal: Journal::default(),
+            ..Self::new()
         }
     }
 
@@ -32,11 +209,36 @@ impl PluginManager {
         self.selected_plugins.clear();
         self.selected_manufacturers.clear();
         self.deletion_error = None;
+        self.trash_results = None;
+        self.duplicates.clear();
+        self.redundant_formats.clear();
+        self.leftovers.clear();
+        self.selected_leftovers.clear();
+        self.details_plugin = None;
+        self.receipts = None;
+        self.packages_to_forget.clear();
+        self.uninstall_entries = None;
+        self.daw_cache_entries = read_daw_caches(&self.daw_readers);
+        if let Some(folder) = self.project_folder.clone() {
+            self.set_project_folder(folder);
+        }
 
         match self.scanner.scan_all_plugins() {
             Ok(plugins) => {
                 let mut grouped_by_key: BTreeMap<String, Vec<Plugin>> = BTreeMap::new();
-                for plugin in plugins {
+                let plugin_dirs = self.scanner.plugin_directories();
+                for mut plugin in plugins {
+                    if plugin.manufacturer_source == ManufacturerSource::Unknown {
+                        if let Some((vendor, source)) =
+                            infer_manufacturer(&plugin, &plugin_dirs, &self.vendor_aliases)
+                        {
+                            plugin.manufacturer = vendor;
+                            plugin.manufacturer_source = source;
+                        }
+                    }
+                    if let Some(canonical) = self.vendor_aliases.resolve(&plugin) {
+                        plugin.manufacturer = canonical.to_string();
+                    }
                     let cleaned_name = clean_manufacturer_name(&plugin.manufacturer);
                     let key = cleaned_name.to_lowercase().replace(['-', ' '], "");
                     grouped_by_key.entry(key).or_default().push(plugin);
@@ -74,7 +276,10 @@ impl PluginManager {
 
                     final_plugins.insert(display_name, group);
                 }
-                self.plugins = final_plugins;
+                self.plugins = self.vendor_overrides.apply(final_plugins);
+                self.refresh_project_usage();
+                self.refresh_version_mismatches();
+                self.refresh_licensing();
             }
             Err(e) => {
                 eprintln!("Error scanning plugins: {}", e);
@@ -83,4 +288,57 @@ impl PluginManager {
 
         self.scanning = false;
     }
+
+    /// What each DAW's plugin cache says is wrong with a plugin.
+    pub fn daw_flags(&self, plugin: &Plugin) -> Vec<&DawCacheEntry> {
+        self.daw_cache_entries
+            .iter()
+            .filter(|entry| entry.matches(plugin))
+            .collect()
+    }
+
+    /// Whether a plugin passes the current list filters.
+    pub fn is_visible(&self, plugin: &Plugin) -> bool {
+        !self.only_unsigned
+            || plugin
+                .signature
+                .as_ref()
+                .is_some_and(|s| s.status.is_anonymous())
+    }
+
+    /// The vendor groups in display order, each with its plugins sorted to match.
+    /// Plugins hidden by the filters are left out, and so are groups left empty.
+    pub fn sorted_plugins(&self) -> Vec<(String, Vec<Plugin>)> {
+        let mut groups: Vec<(String, Vec<Plugin>)> = self
+            .plugins
+            .iter()
+            .map(|(k, v)| {
+                let visible: Vec<Plugin> =
+                    v.iter().filter(|p| self.is_visible(p)).cloned().collect();
+                (k.clone(), visible)
+            })
+            .filter(|(_, v)| !v.is_empty())
+            .collect();
+
+        if self.sort_order == SortOrder::LargestFirst {
+            for (_, plugins) in &mut groups {
+                plugins.sort_by_key(|p| std::cmp::Reverse(p.size));
+            }
+            groups.sort_by_key(|(_, plugins)| {
+                std::cmp::Reverse(plugins.iter().map(|p| p.size).sum::<u64>())
+            });
+        }
+
+        if self.sort_order == SortOrder::Version {
+            for (_, plugins) in &mut groups {
+                // Unknown versions go last rather than first.
+                plugins.sort_by_cached_key(|p| {
+                    let version = p.parsed_version();
+                    (version.is_none(), version)
+                });
+            }
+        }
+
+        groups
+    }
 }
@@ -0,0 +1,65 @@
+use super::state::PluginManager;
+use crate::plugin::{ManifestEntry, Plugin, UpdateManifest};
+use eframe::egui;
+use std::sync::mpsc::{self, TryRecvError};
+use std::time::Duration;
+
+impl PluginManager {
+    pub fn choose_update_manifest(&mut self) {
+        if let Some(file) = rfd::FileDialog::new()
+            .set_title("Choose an update manifest")
+            .add_filter("JSON", &["json"])
+            .pick_file()
+        {
+            self.update_source = file.display().to_string();
+            self.check_for_updates();
+        }
+    }
+
+    /// Starts loading the manifest from the file or URL in `update_source` on another
+    /// thread, so a slow server doesn't freeze the window. `poll_update_check` picks up
+    /// the result.
+    pub fn check_for_updates(&mut self) {
+        let source = self.update_source.trim().to_string();
+        let (sender, receiver) = mpsc::channel();
+        let fetch_source = source.clone();
+        std::thread::spawn(move || {
+            let _ = sender.send(UpdateManifest::load(&fetch_source));
+        });
+        self.update_error = None;
+        self.update_check = Some((source, receiver));
+    }
+
+    /// Applies a finished update check, remembering its source for next time. Keeps
+    /// the UI repainting while one is still running.
+    pub(super) fn poll_update_check(&mut self, ctx: &egui::Context) {
+        let Some((source, receiver)) = &self.update_check else {
+            return;
+        };
+        let result = match receiver.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => {
+                ctx.request_repaint_after(Duration::from_millis(100));
+                return;
+            }
+            Err(TryRecvError::Disconnected) => Err(anyhow::anyhow!("The update check stopped")),
+        };
+        let source = source.clone();
+        self.update_check = None;
+
+        match result {
+            Ok(manifest) => {
+                self.update_manifest = Some(manifest);
+                self.update_feed.source = Some(source);
+                if let Err(e) = self.update_feed.save() {
+                    eprintln!("Error saving update feed: {}", e);
+                }
+            }
+            Err(e) => self.update_error = Some(format!("{:#}", e)),
+        }
+    }
+
+    pub fn available_update(&self, plugin: &Plugin) -> Option<&ManifestEntry> {
+        self.update_manifest.as_ref()?.update_for(plugin)
+    }
+}
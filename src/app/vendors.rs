@@ -0,0 +1,32 @@
+use super::state::PluginManager;
+use crate::plugin::Plugin;
+
+impl PluginManager {
+    pub fn reassign_plugin(&mut self, plugin: &Plugin, vendor: &str) {
+        if plugin.manufacturer == vendor {
+            return;
+        }
+        self.vendor_overrides.assign_plugin(plugin, vendor);
+        self.apply_vendor_overrides();
+    }
+
+    /// Renames a vendor group, merging it into `to` if that group already exists.
+    pub fn rename_vendor(&mut self, from: &str, to: &str) {
+        let to = to.trim();
+        if to.is_empty() || from == to {
+            return;
+        }
+        self.vendor_overrides.rename_group(from, to);
+        self.apply_vendor_overrides();
+    }
+
+    fn apply_vendor_overrides(&mut self) {
+        if let Err(e) = self.vendor_overrides.save() {
+            eprintln!("Error saving vendor overrides: {}", e);
+        }
+        self.plugins = self.vendor_overrides.apply(std::mem::take(&mut self.plugins));
+        self.refresh_manufacturer_selection();
+        self.refresh_version_mismatches();
+        self.refresh_licensing();
+    }
+}
@@ -0,0 +1,19 @@
+use super::state::PluginManager;
+use crate::plugin::find_version_mismatches;
+
+impl PluginManager {
+    /// Notes every plugin whose product has other formats installed on different
+    /// versions, describing those versions for the list.
+    pub(super) fn refresh_version_mismatches(&mut self) {
+        self.version_mismatches = find_version_mismatches(self.plugins.values().flatten())
+            .into_iter()
+            .map(|(plugin, others)| {
+                let versions: Vec<String> = others
+                    .iter()
+                    .map(|(format, version)| format!("{:?} is on {}", format, version))
+                    .collect();
+                (plugin.path.clone(), versions.join(", "))
+            })
+            .collect();
+    }
+}
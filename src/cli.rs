@@ -0,0 +1,158 @@
+use crate::app::PluginManager;
+use crate::plugin::{Rule, UpdateManifest, RULE_HELP};
+use crate::utils::elevation::{run_helper, HELPER_COMMAND};
+use crate::utils::error::Result;
+use anyhow::{bail, Context};
+use std::path::PathBuf;
+
+const USAGE: &str = "Usage: plugin-manager plan [--vendor NAME]... [--rule EXPR]... \
+[--projects DIR] [--unlock] [PATH]...
+       plugin-manager updates MANIFEST";
+
+/// Runs a command-line subcommand if one was given, returning the exit code. Anything
+/// else starts the GUI, since macOS and file managers may pass their own arguments.
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, rest) = args.split_first()?;
+    let command: fn(&[String]) -> Result<()> = match command.as_str() {
+        "plan" => print_plan,
+        "updates" => print_updates,
+        "help" | "--help" | "-h" => print_usage,
+        HELPER_COMMAND => run_helper,
+        _ => return None,
+    };
+
+    #[cfg(target_os = "windows")]
+    attach_console();
+
+    match command(rest) {
+        Ok(()) => Some(0),
+        Err(e) => {
+            eprintln!("Error: {}\n{}", e, USAGE);
+            Some(1)
+        }
+    }
+}
+
+/// The release build has no console of its own, so output goes to the console of
+/// whatever started it.
+#[cfg(target_os = "windows")]
+fn attach_console() {
+    unsafe {
+        winapi::um::wincon::AttachConsole(winapi::um::wincon::ATTACH_PARENT_PROCESS);
+    }
+}
+
+fn print_usage(_args: &[String]) -> Result<()> {
+    println!("{}", USAGE);
+    Ok(())
+}
+
+/// Prints the removal plan for the given vendors and paths as JSON without touching
+/// anything on disk.
+fn print_plan(args: &[String]) -> Result<()> {
+    let mut vendors = Vec::new();
+    let mut rules = Vec::new();
+    let mut paths = Vec::new();
+    let mut project_folder = None;
+    let mut unlock = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--vendor" => vendors.push(args.next().context("--vendor needs a name")?.clone()),
+            "--projects" => {
+                project_folder = Some(PathBuf::from(
+                    args.next().context("--projects needs a folder")?,
+                ))
+            }
+            "--rule" => {
+                let text = args.next().context("--rule needs an expression")?;
+                rules.push(Rule::parse(text).with_context(|| RULE_HELP)?);
+            }
+            "--unlock" => unlock = true,
+            flag if flag.starts_with("--") => bail!("Unknown option {}", flag),
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+    if vendors.is_empty() && rules.is_empty() && paths.is_empty() {
+        bail!("Nothing to plan; pass a vendor, a rule or a path");
+    }
+
+    let mut manager = PluginManager::new();
+    if let Some(error) = &manager.protect_list_error {
+        bail!(
+            "Nothing can be planned while the protect list is unreadable: {}",
+            error
+        );
+    }
+    manager.scan_plugins();
+    if let Some(folder) = project_folder {
+        manager.set_project_folder(folder);
+        manager.wait_for_project_scan();
+    }
+
+    // Protected plugins are left out of the plan unless they're explicitly unlocked.
+    if unlock {
+        let scanned: Vec<PathBuf> = manager
+            .plugins
+            .values()
+            .flatten()
+            .map(|p| p.path.clone())
+            .collect();
+        manager.unlocked.extend(scanned);
+        manager.unlocked.extend(paths.iter().cloned());
+    }
+
+    for vendor in &vendors {
+        let Some(name) = manager
+            .plugins
+            .keys()
+            .find(|name| name.eq_ignore_ascii_case(vendor))
+            .cloned()
+        else {
+            bail!("No plugins found for vendor {:?}", vendor);
+        };
+        if !manager.selected_manufacturers.contains(&name) {
+            manager.toggle_manufacturer(&name);
+        }
+    }
+    for rule in &rules {
+        manager.select_by_rule(rule, false);
+    }
+    manager.selected_plugins.extend(paths);
+
+    println!("{}", serde_json::to_string_pretty(&manager.removal_plan())?);
+    Ok(())
+}
+
+/// Prints the plugins the manifest (a file path or URL) lists newer versions of, as
+/// JSON.
+fn print_updates(args: &[String]) -> Result<()> {
+    let [source] = args else {
+        bail!("Pass one manifest file or URL");
+    };
+    let manifest = UpdateManifest::load(source)?;
+
+    let mut manager = PluginManager::new();
+    manager.scan_plugins();
+    let updates: Vec<_> = manager
+        .plugins
+        .values()
+        .flatten()
+        .filter_map(|plugin| {
+            let entry = manifest.update_for(plugin)?;
+            Some(serde_json::json!({
+                "vendor": plugin.manufacturer,
+                "name": plugin.name,
+                "format": plugin.plugin_type,
+                "path": plugin.path,
+                "installed_version": plugin.version,
+                "latest_version": entry.latest_version,
+                "download_url": entry.download_url,
+            }))
+        })
+        .collect();
+
+    println!("{}", serde_json::to_string_pretty(&updates)?);
+    Ok(())
+}
@@ -5,6 +5,7 @@ use eframe::egui;
 use egui::IconData;
 
 mod app;
+mod cli;
 mod plugin;
 mod scanner;
 mod utils;
@@ -30,6 +31,11 @@ fn load_icon() -> IconData {
 }
 
 fn main() -> Result<(), eframe::Error> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([800.0, 600.0])
@@ -0,0 +1,200 @@
+use super::types::Plugin;
+use super::utils::{clean_manufacturer_name, normalize_name};
+use crate::utils::config::load_json;
+use serde::{Deserialize, Serialize};
+
+const BUILT_IN_ALIASES: &str = include_str!("vendor_aliases.json");
+pub const USER_ALIASES_FILE: &str = "vendor_aliases.json";
+
+/// The on-disk alias table. The built-in table and the user's override file share
+/// this format; `version` is bumped if the shape of an entry ever changes, and a file
+/// without one is read as version 1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AliasFile {
+    pub version: u32,
+    pub vendors: Vec<VendorAlias>,
+}
+
+impl Default for AliasFile {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            vendors: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VendorAlias {
+    /// The canonical vendor name every match is grouped under.
+    pub name: String,
+    /// Other spellings of the vendor name, compared ignoring case and punctuation.
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Reverse-DNS prefixes such as `com.fabfilter`, matched on whole components.
+    #[serde(default)]
+    pub bundle_id_prefixes: Vec<String>,
+    /// Four-character AudioComponent manufacturer codes, matched exactly.
+    #[serde(default)]
+    pub au_manufacturer_codes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VendorAliases {
+    vendors: Vec<VendorAlias>,
+}
+
+impl VendorAliases {
+    /// The built-in table with the user's overrides layered on top.
+    pub fn load() -> Self {
+        let user: AliasFile = load_json(USER_ALIASES_FILE);
+        Self::from_tables(user, Self::built_in())
+    }
+
+    pub fn built_in() -> AliasFile {
+        serde_json::from_str(BUILT_IN_ALIASES).expect("Built-in vendor alias table is invalid")
+    }
+
+    /// Entries from `overrides` are consulted first, so a user can claim a code, prefix
+    /// or spelling that the built-in table gives to another vendor.
+    pub fn from_tables(overrides: AliasFile, built_in: AliasFile) -> Self {
+        let mut vendors = overrides.vendors;
+        vendors.extend(built_in.vendors);
+        Self { vendors }
+    }
+
+    /// The canonical vendor for `plugin`, trying its bundle identifier first, then its
+    /// AU manufacturer code, then the manufacturer name it reported.
+    pub fn resolve(&self, plugin: &Plugin) -> Option<&str> {
+        plugin
+            .bundle_id
+            .as_deref()
+            .and_then(|id| self.by_bundle_id(id))
+            .or_else(|| {
+                plugin
+                    .manufacturer_code
+                    .as_deref()
+                    .and_then(|code| self.by_au_code(code))
+            })
+            .or_else(|| self.by_name(&plugin.manufacturer))
+    }
+
+    pub fn by_bundle_id(&self, bundle_id: &str) -> Option<&str> {
+        let bundle_id = bundle_id.to_lowercase();
+        let mut best: Option<(usize, &str)> = None;
+
+        for vendor in &self.vendors {
+            for prefix in &vendor.bundle_id_prefixes {
+                let prefix = prefix.to_lowercase();
+                let matches = bundle_id == prefix
+                    || bundle_id
+                        .strip_prefix(&prefix)
+                        .is_some_and(|rest| rest.starts_with('.'));
+                if matches && best.is_none_or(|(len, _)| prefix.len() > len) {
+                    best = Some((prefix.len(), &vendor.name));
+                }
+            }
+        }
+
+        best.map(|(_, name)| name)
+    }
+
+    pub fn by_au_code(&self, code: &str) -> Option<&str> {
+        self.vendors
+            .iter()
+            .find(|v| v.au_manufacturer_codes.iter().any(|c| c == code))
+            .map(|v| v.name.as_str())
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&str> {
+        let wanted = normalize_name(&clean_manufacturer_name(name));
+        if wanted.is_empty() {
+            return None;
+        }
+
+        self.vendors
+            .iter()
+            .find(|v| {
+                std::iter::once(&v.name)
+                    .chain(&v.aliases)
+                    .any(|alias| normalize_name(&clean_manufacturer_name(alias)) == wanted)
+            })
+            .map(|v| v.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::PluginType;
+
+    fn plugin(manufacturer: &str, bundle_id: Option<&str>, code: Option<&str>) -> Plugin {
+        Plugin {
+            bundle_id: bundle_id.map(str::to_string),
+            manufacturer_code: code.map(str::to_string),
+            ..Plugin::test("Plugin", manufacturer, PluginType::AU)
+        }
+    }
+
+    fn built_in() -> VendorAliases {
+        VendorAliases::from_tables(AliasFile::default(), VendorAliases::built_in())
+    }
+
+    #[test]
+    fn built_in_table_parses() {
+        let table = VendorAliases::built_in();
+        assert!(!table.vendors.is_empty());
+        assert!(table.vendors.iter().all(|v| !v.name.is_empty()));
+    }
+
+    #[test]
+    fn resolves_by_au_code() {
+        let aliases = built_in();
+        assert_eq!(
+            aliases.resolve(&plugin("FF", None, Some("FabF"))),
+            Some("FabFilter")
+        );
+        assert_eq!(aliases.by_au_code("fabf"), None);
+    }
+
+    #[test]
+    fn resolves_by_bundle_id_prefix() {
+        let aliases = built_in();
+        assert_eq!(
+            aliases.resolve(&plugin("Unknown", Some("com.fabfilter.Pro-Q.3"), None)),
+            Some("FabFilter")
+        );
+        assert_eq!(
+            aliases.by_bundle_id("com.native-instruments"),
+            Some("Native Instruments")
+        );
+        assert_eq!(aliases.by_bundle_id("com.fabfilterfake.plugin"), None);
+    }
+
+    #[test]
+    fn resolves_by_name_alias() {
+        let aliases = built_in();
+        assert_eq!(
+            aliases.resolve(&plugin("Digidesign", None, None)),
+            Some("Avid")
+        );
+        assert_eq!(
+            aliases.resolve(&plugin("native-instruments gmbh", None, None)),
+            Some("Native Instruments")
+        );
+        assert_eq!(aliases.resolve(&plugin("Someone Else", None, None)), None);
+    }
+
+    #[test]
+    fn overrides_win_and_need_no_version() {
+        let overrides: AliasFile = serde_json::from_str(
+            r#"{"vendors": [{"name": "FF Audio", "au_manufacturer_codes": ["FabF"]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(overrides.version, 1);
+
+        let aliases = VendorAliases::from_tables(overrides, VendorAliases::built_in());
+        assert_eq!(aliases.by_au_code("FabF"), Some("FF Audio"));
+    }
+}
@@ -0,0 +1,158 @@
+use super::types::{Plugin, PluginType};
+use super::utils::normalize_name;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Copies of the same plugin, in the same format, installed in more than one place.
+#[derive(Debug, Clone)]
+pub struct DuplicateSet {
+    pub plugins: Vec<Plugin>,
+    /// Index into `plugins` of the copy a host will most likely load.
+    pub preferred: usize,
+}
+
+impl DuplicateSet {
+    pub fn preferred_plugin(&self) -> &Plugin {
+        &self.plugins[self.preferred]
+    }
+
+    /// Every copy other than the one a host is expected to load.
+    pub fn redundant_paths(&self) -> Vec<PathBuf> {
+        self.plugins
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != self.preferred)
+            .map(|(_, p)| p.path.clone())
+            .collect()
+    }
+}
+
+pub fn find_duplicates<'a>(plugins: impl IntoIterator<Item = &'a Plugin>) -> Vec<DuplicateSet> {
+    let home = dirs::home_dir();
+    let mut groups: HashMap<(PluginType, String, String), Vec<Plugin>> = HashMap::new();
+
+    // A disabled copy isn't loaded by anything, so it can't shadow another.
+    for plugin in plugins.into_iter().filter(|p| !p.disabled) {
+        groups
+            .entry(duplicate_key(plugin))
+            .or_default()
+            .push(plugin.clone());
+    }
+
+    let mut sets: Vec<DuplicateSet> = groups
+        .into_values()
+        .flat_map(split_by_bundle_id)
+        .filter(|group| group.len() > 1)
+        .map(|mut group| {
+            group.sort_by(|a, b| a.path.cmp(&b.path));
+            let preferred = group
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| load_preference(a, b, home.as_deref()))
+                .map(|(i, _)| i)
+                .unwrap_or(0);
+            DuplicateSet {
+                plugins: group,
+                preferred,
+            }
+        })
+        .collect();
+
+    sets.sort_by(|a, b| {
+        let a = a.preferred_plugin();
+        let b = b.preferred_plugin();
+        a.name
+            .to_lowercase()
+            .cmp(&b.name.to_lowercase())
+            .then_with(|| a.plugin_type.cmp(&b.plugin_type))
+    });
+    sets
+}
+
+fn duplicate_key(plugin: &Plugin) -> (PluginType, String, String) {
+    (
+        plugin.plugin_type.clone(),
+        normalize_name(&plugin.manufacturer),
+        normalize_name(&plugin.name),
+    )
+}
+
+/// Splits copies with the same vendor and name by bundle ID, since two different IDs
+/// mean two different products. Copies without an ID join the rest when there's only
+/// one ID among them to confirm against.
+fn split_by_bundle_id(group: Vec<Plugin>) -> Vec<Vec<Plugin>> {
+    let mut by_id: BTreeMap<Option<String>, Vec<Plugin>> = BTreeMap::new();
+    for plugin in group {
+        let id = plugin.bundle_id.as_ref().map(|id| id.to_lowercase());
+        by_id.entry(id).or_default().push(plugin);
+    }
+    let unidentified = by_id.remove(&None).unwrap_or_default();
+
+    if by_id.len() <= 1 {
+        let mut group: Vec<Plugin> = by_id.into_values().flatten().collect();
+        group.extend(unidentified);
+        return vec![group];
+    }
+    by_id.into_values().chain([unidentified]).collect()
+}
+
+/// Hosts generally keep the newest copy they find, and search the per-user
+/// folders before the system-wide ones, so a user-domain copy wins a tie.
+fn load_preference(a: &Plugin, b: &Plugin, home: Option<&Path>) -> Ordering {
+    let in_user_domain = |p: &Plugin| home.is_some_and(|h| p.path.starts_with(h));
+
+    a.parsed_version()
+        .cmp(&b.parsed_version())
+        .then_with(|| in_user_domain(a).cmp(&in_user_domain(b)))
+        .then_with(|| b.path.cmp(&a.path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugin(path: &str, manufacturer: &str, bundle_id: Option<&str>) -> Plugin {
+        Plugin {
+            bundle_id: bundle_id.map(str::to_string),
+            path: path.into(),
+            ..Plugin::test("Pro-Q 3", manufacturer, PluginType::VST3)
+        }
+    }
+
+    fn groups(plugins: &[Plugin]) -> Vec<Vec<String>> {
+        find_duplicates(plugins)
+            .into_iter()
+            .map(|set| {
+                set.plugins
+                    .iter()
+                    .map(|p| p.path.display().to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn copies_without_a_bundle_id_still_match() {
+        let plugins = [
+            plugin("/a/Pro-Q 3.vst3", "FabFilter", Some("com.fabfilter.proq3")),
+            plugin("/b/Pro-Q 3.vst3", "Fabfilter", None),
+            plugin("/c/Pro-Q 3.vst3", "FabFilter", Some("com.FabFilter.ProQ3")),
+        ];
+        assert_eq!(
+            groups(&plugins),
+            [["/a/Pro-Q 3.vst3", "/b/Pro-Q 3.vst3", "/c/Pro-Q 3.vst3"]]
+        );
+    }
+
+    #[test]
+    fn different_bundle_ids_are_different_products() {
+        let plugins = [
+            plugin("/a/Pro-Q 3.vst3", "FabFilter", Some("com.fabfilter.proq3")),
+            plugin("/b/Pro-Q 3.vst3", "FabFilter", Some("com.example.proq3")),
+            plugin("/c/Pro-Q 3.vst3", "FabFilter", None),
+            plugin("/d/Pro-Q 3.vst3", "Other", None),
+        ];
+        assert!(groups(&plugins).is_empty());
+    }
+}
@@ -0,0 +1,205 @@
+use super::types::{Plugin, PluginType};
+use super::utils::normalize_name;
+use crate::utils::config::{load_json, save_json};
+use crate::utils::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+pub const FORMAT_POLICY_FILE: &str = "format_policy.json";
+
+/// The formats to keep for each product; other formats of a product are redundant
+/// once one of these is installed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatPolicy {
+    pub keep: BTreeSet<PluginType>,
+}
+
+impl Default for FormatPolicy {
+    #[cfg(target_os = "macos")]
+    fn default() -> Self {
+        Self {
+            keep: BTreeSet::from([PluginType::AU, PluginType::VST3]),
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn default() -> Self {
+        Self {
+            keep: BTreeSet::from([PluginType::VST3]),
+        }
+    }
+}
+
+impl FormatPolicy {
+    pub fn load() -> Self {
+        load_json(FORMAT_POLICY_FILE)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        save_json(FORMAT_POLICY_FILE, self)
+    }
+
+    pub fn keeps(&self, format: &PluginType) -> bool {
+        self.keep.contains(format)
+    }
+}
+
+/// A plugin in a format the policy doesn't keep, for a product that is also installed
+/// in a format it does.
+#[derive(Debug, Clone)]
+pub struct RedundantFormat {
+    pub plugin: Plugin,
+    /// The enabled copies that make this one redundant.
+    pub kept: Vec<Plugin>,
+}
+
+impl RedundantFormat {
+    pub fn reason(&self) -> String {
+        let formats: BTreeSet<String> = self
+            .kept
+            .iter()
+            .map(|p| format!("{:?}", p.plugin_type))
+            .collect();
+        let versions = if formats.len() == 1 {
+            "version is"
+        } else {
+            "versions are"
+        };
+        format!(
+            "{:?} isn't a preferred format, and the {} {} installed",
+            self.plugin.plugin_type,
+            formats.into_iter().collect::<Vec<_>>().join(" and "),
+            versions
+        )
+    }
+}
+
+/// Groups plugins into products by normalized vendor and name, and proposes every copy
+/// in a format outside the policy when the product has an enabled copy inside it.
+/// Products with no preferred format installed are left alone so nothing disappears.
+pub fn find_redundant_formats<'a>(
+    plugins: impl IntoIterator<Item = &'a Plugin>,
+    policy: &FormatPolicy,
+) -> Vec<RedundantFormat> {
+    let mut products: BTreeMap<(String, String), Vec<&Plugin>> = BTreeMap::new();
+    for plugin in plugins {
+        products
+            .entry((
+                normalize_name(&plugin.manufacturer),
+                normalize_name(&plugin.name),
+            ))
+            .or_default()
+            .push(plugin);
+    }
+
+    let mut redundant = Vec::new();
+    for copies in products.into_values() {
+        let kept: Vec<Plugin> = copies
+            .iter()
+            .filter(|p| policy.keeps(&p.plugin_type) && !p.disabled)
+            .map(|p| (*p).clone())
+            .collect();
+        if kept.is_empty() {
+            continue;
+        }
+        for plugin in copies {
+            if !policy.keeps(&plugin.plugin_type) {
+                redundant.push(RedundantFormat {
+                    plugin: plugin.clone(),
+                    kept: kept.clone(),
+                });
+            }
+        }
+    }
+    redundant
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(keep: &[PluginType]) -> FormatPolicy {
+        FormatPolicy {
+            keep: keep.iter().cloned().collect(),
+        }
+    }
+
+    fn redundant_paths(plugins: &[Plugin], policy: &FormatPolicy) -> Vec<String> {
+        find_redundant_formats(plugins, policy)
+            .into_iter()
+            .map(|r| r.plugin.path.display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn proposes_copies_outside_the_policy() {
+        let plugins = [
+            Plugin::test("Pro-Q 3", "FabFilter", PluginType::VST3),
+            Plugin::test("Pro-Q 3", "FabFilter", PluginType::VST2),
+            Plugin::test("Pro-Q 3", "FabFilter", PluginType::AAX),
+        ];
+        assert_eq!(
+            redundant_paths(&plugins, &policy(&[PluginType::VST3])),
+            ["/plugins/Pro-Q 3.VST2", "/plugins/Pro-Q 3.AAX"]
+        );
+    }
+
+    #[test]
+    fn products_without_a_preferred_format_are_left_alone() {
+        let plugins = [
+            Plugin::test("Serum", "Xfer Records", PluginType::VST2),
+            Plugin::test("Serum", "Xfer Records", PluginType::AU),
+        ];
+        assert!(redundant_paths(&plugins, &policy(&[PluginType::VST3])).is_empty());
+    }
+
+    #[test]
+    fn a_disabled_preferred_copy_keeps_the_others() {
+        let plugins = [
+            Plugin {
+                disabled: true,
+                ..Plugin::test("Serum", "Xfer Records", PluginType::VST3)
+            },
+            Plugin::test("Serum", "Xfer Records", PluginType::VST2),
+        ];
+        assert!(redundant_paths(&plugins, &policy(&[PluginType::VST3])).is_empty());
+    }
+
+    #[test]
+    fn products_are_told_apart_by_vendor() {
+        let plugins = [
+            Plugin::test("Compressor", "Waves", PluginType::VST3),
+            Plugin::test("Compressor", "Acme", PluginType::VST2),
+        ];
+        assert!(redundant_paths(&plugins, &policy(&[PluginType::VST3])).is_empty());
+
+        let same_vendor = [
+            Plugin::test("Compressor", "Acme Audio", PluginType::VST3),
+            Plugin::test("compressor", "ACME-audio", PluginType::VST2),
+        ];
+        assert_eq!(
+            redundant_paths(&same_vendor, &policy(&[PluginType::VST3])),
+            ["/plugins/compressor.VST2"]
+        );
+    }
+
+    #[test]
+    fn reason_names_the_kept_formats() {
+        let plugins = [
+            Plugin::test("Pro-Q 3", "FabFilter", PluginType::VST3),
+            Plugin::test("Pro-Q 3", "FabFilter", PluginType::AU),
+            Plugin::test("Pro-Q 3", "FabFilter", PluginType::VST2),
+        ];
+        let redundant =
+            find_redundant_formats(&plugins, &policy(&[PluginType::AU, PluginType::VST3]));
+        assert_eq!(redundant.len(), 1);
+        assert_eq!(
+            redundant[0].reason(),
+            "VST2 isn't a preferred format, and the AU and VST3 versions are installed"
+        );
+        assert_eq!(
+            find_redundant_formats(&plugins[1..], &policy(&[PluginType::AU]))[0].reason(),
+            "VST2 isn't a preferred format, and the AU version is installed"
+        );
+    }
+}
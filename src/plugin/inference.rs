@@ -0,0 +1,294 @@
+use super::aliases::VendorAliases;
+use super::types::Plugin;
+use super::utils::clean_manufacturer_name;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Where a plugin's vendor name came from, from most to least trustworthy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ManufacturerSource {
+    /// Set by hand in the UI.
+    Manual,
+    /// Declared by the plugin itself (AudioComponents, CompanyName, moduleinfo.json).
+    Metadata,
+    CodeSigning,
+    BundleIdentifier,
+    Copyright,
+    ParentFolder,
+    AliasDatabase,
+    Unknown,
+}
+
+impl ManufacturerSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ManufacturerSource::Manual => "set manually",
+            ManufacturerSource::Metadata => "plugin metadata",
+            ManufacturerSource::CodeSigning => "code signature",
+            ManufacturerSource::BundleIdentifier => "bundle identifier",
+            ManufacturerSource::Copyright => "copyright notice",
+            ManufacturerSource::ParentFolder => "parent folder",
+            ManufacturerSource::AliasDatabase => "vendor alias database",
+            ManufacturerSource::Unknown => "not found",
+        }
+    }
+
+    pub fn confidence(&self) -> &'static str {
+        match self {
+            ManufacturerSource::Manual
+            | ManufacturerSource::Metadata
+            | ManufacturerSource::CodeSigning => "high",
+            ManufacturerSource::BundleIdentifier | ManufacturerSource::AliasDatabase => "medium",
+            ManufacturerSource::Copyright | ManufacturerSource::ParentFolder => "low",
+            ManufacturerSource::Unknown => "none",
+        }
+    }
+
+    pub fn is_inferred(&self) -> bool {
+        !matches!(
+            self,
+            ManufacturerSource::Manual | ManufacturerSource::Metadata
+        )
+    }
+}
+
+/// Folder names that sort plugins by format or architecture rather than by vendor.
+const GENERIC_FOLDERS: [&str; 14] = [
+    "vst",
+    "vst2",
+    "vst3",
+    "vstplugins",
+    "plug-ins",
+    "plugins",
+    "components",
+    "x64",
+    "x86",
+    "64-bit",
+    "32-bit",
+    "effects",
+    "instruments",
+    "common files",
+];
+
+/// Bundle identifier components that belong to a template or a platform, not a vendor.
+const GENERIC_IDENTIFIERS: [&str; 5] = ["yourcompany", "juce", "apple", "audio", "plugin"];
+
+/// Guesses a vendor for a plugin whose metadata didn't name one, trying the parent
+/// folder, the bundle identifier, the copyright notice, the code-signing identity and
+/// finally the alias database.
+/// `plugin_dirs` are the scanner's search folders, which never count as vendor folders.
+pub fn infer_manufacturer(
+    plugin: &Plugin,
+    plugin_dirs: &[PathBuf],
+    aliases: &VendorAliases,
+) -> Option<(String, ManufacturerSource)> {
+    from_parent_folder(plugin, plugin_dirs)
+        .map(|v| (v, ManufacturerSource::ParentFolder))
+        .or_else(|| {
+            from_bundle_id(plugin, aliases).map(|v| (v, ManufacturerSource::BundleIdentifier))
+        })
+        .or_else(|| {
+            plugin
+                .copyright
+                .as_deref()
+                .and_then(vendor_from_copyright)
+                .map(|v| (v, ManufacturerSource::Copyright))
+        })
+        .or_else(|| {
+            plugin
+                .signature
+                .as_ref()
+                .and_then(|s| s.publisher())
+                .map(|v| (clean_manufacturer_name(&v), ManufacturerSource::CodeSigning))
+        })
+        .or_else(|| {
+            let by_id = plugin.bundle_id.as_deref().and_then(|id| aliases.by_bundle_id(id));
+            let by_code = plugin
+                .manufacturer_code
+                .as_deref()
+                .and_then(|code| aliases.by_au_code(code));
+            by_id
+                .or(by_code)
+                .map(|v| (v.to_string(), ManufacturerSource::AliasDatabase))
+        })
+}
+
+fn from_parent_folder(plugin: &Plugin, plugin_dirs: &[PathBuf]) -> Option<String> {
+    let root = plugin_dirs
+        .iter()
+        .filter(|dir| plugin.path.starts_with(dir))
+        .max_by_key(|dir| dir.components().count())?;
+
+    // Only the folder directly beneath the search root is treated as a vendor folder;
+    // anything deeper tends to be a product or a bitness split.
+    let relative = plugin.path.strip_prefix(root).ok()?;
+    let mut components = relative.components();
+    let first = components.next()?.as_os_str().to_str()?;
+    components.next()?;
+
+    let is_generic = GENERIC_FOLDERS
+        .iter()
+        .any(|g| first.eq_ignore_ascii_case(g));
+    (!is_generic).then(|| first.to_string())
+}
+
+/// The vendor component of a reverse-DNS identifier, spelled the way the alias table
+/// spells it, or capitalized when the identifier is all lowercase, so
+/// `com.valhalladsp.supermassive` gives "Valhalla DSP" and `com.acme.synth` "Acme".
+fn from_bundle_id(plugin: &Plugin, aliases: &VendorAliases) -> Option<String> {
+    let id = plugin.bundle_id.as_deref()?;
+    let parts: Vec<&str> = id.split('.').collect();
+    if parts.len() < 3 {
+        return None;
+    }
+
+    let vendor = parts[1];
+    let is_generic = vendor.is_empty()
+        || GENERIC_IDENTIFIERS
+            .iter()
+            .any(|g| vendor.eq_ignore_ascii_case(g));
+    if is_generic {
+        return None;
+    }
+    if let Some(name) = aliases.by_name(vendor) {
+        return Some(name.to_string());
+    }
+    if vendor.chars().any(char::is_uppercase) {
+        return Some(vendor.to_string());
+    }
+    let mut chars = vendor.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+}
+
+/// Pulls the holder out of notices like "Copyright © 2012-2023 Valhalla DSP, LLC. All
+/// rights reserved." or "(c) 2020 by Tokyo Dawn Labs".
+fn vendor_from_copyright(copyright: &str) -> Option<String> {
+    static RESERVED: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"(?i)[.,;]?\s*all rights reserved.*$").unwrap());
+    static NOTICE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"(?i)copyright|\(c\)|©|\b(19|20)\d{2}\b").unwrap());
+
+    let without_reserved = RESERVED.replace(copyright, "");
+    let holder = NOTICE.replace_all(&without_reserved, " ");
+    let holder = holder.split_whitespace().collect::<Vec<_>>().join(" ");
+    let holder = holder.trim_matches(|c: char| !c.is_alphanumeric());
+    let holder = holder
+        .strip_prefix("by ")
+        .or_else(|| holder.strip_prefix("By "))
+        .unwrap_or(holder);
+    let holder = clean_manufacturer_name(holder);
+
+    (holder.chars().filter(|c| c.is_alphanumeric()).count() >= 2).then_some(holder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::aliases::AliasFile;
+    use crate::plugin::PluginType;
+
+    fn aliases() -> VendorAliases {
+        VendorAliases::from_tables(AliasFile::default(), VendorAliases::built_in())
+    }
+
+    fn plugin(path: &str, bundle_id: Option<&str>, copyright: Option<&str>) -> Plugin {
+        Plugin {
+            path: path.into(),
+            bundle_id: bundle_id.map(str::to_string),
+            copyright: copyright.map(str::to_string),
+            ..Plugin::test("Plugin", "", PluginType::VST3)
+        }
+    }
+
+    fn infer(plugin: &Plugin) -> Option<(String, ManufacturerSource)> {
+        infer_manufacturer(plugin, &[PathBuf::from("/VST3")], &aliases())
+    }
+
+    #[test]
+    fn copyright_holders() {
+        for (notice, holder) in [
+            (
+                "Copyright © 2012-2023 Valhalla DSP, LLC. All rights reserved.",
+                "Valhalla DSP",
+            ),
+            ("(c) 2020 by Tokyo Dawn Labs", "Tokyo Dawn Labs"),
+            ("Copyright (C) 2019 Xfer Records Inc.", "Xfer Records"),
+            ("© u-he 2021", "u-he"),
+        ] {
+            assert_eq!(vendor_from_copyright(notice).as_deref(), Some(holder));
+        }
+        assert_eq!(
+            vendor_from_copyright("Copyright 2020. All rights reserved."),
+            None
+        );
+    }
+
+    #[test]
+    fn only_the_folder_below_the_search_root_is_a_vendor() {
+        let nested = plugin("/VST3/Valhalla/Supermassive.vst3", None, None);
+        assert_eq!(
+            infer(&nested),
+            Some(("Valhalla".to_string(), ManufacturerSource::ParentFolder))
+        );
+        assert_eq!(infer(&plugin("/VST3/Supermassive.vst3", None, None)), None);
+        assert_eq!(
+            infer(&plugin("/VST3/x64/Supermassive.vst3", None, None)),
+            None
+        );
+        assert_eq!(
+            infer(&plugin("/VST3/Plugins/Supermassive.vst3", None, None)),
+            None
+        );
+    }
+
+    #[test]
+    fn bundle_ids_use_alias_spelling_or_capitalize() {
+        let by_id = |id: &str| from_bundle_id(&plugin("/VST3/X.vst3", Some(id), None), &aliases());
+        assert_eq!(
+            by_id("com.valhalladsp.Supermassive").as_deref(),
+            Some("Valhalla DSP")
+        );
+        assert_eq!(by_id("com.acme.synth").as_deref(), Some("Acme"));
+        assert_eq!(by_id("com.AcmeAudio.synth").as_deref(), Some("AcmeAudio"));
+        assert_eq!(by_id("com.acme").as_deref(), None);
+    }
+
+    #[test]
+    fn template_and_platform_identifiers_are_skipped() {
+        let by_id = |id: &str| from_bundle_id(&plugin("/VST3/X.vst3", Some(id), None), &aliases());
+        for id in [
+            "com.juce.pluginhost",
+            "com.yourcompany.NewProject",
+            "com.apple.audio.units.Components",
+            "com.Plugin.thing",
+        ] {
+            assert_eq!(by_id(id), None, "{}", id);
+        }
+    }
+
+    #[test]
+    fn sources_are_tried_in_order() {
+        let copyright = Some("(c) 2020 by Tokyo Dawn Labs");
+        let folder_first = plugin("/VST3/TDR/Nova.vst3", Some("com.acme.nova"), copyright);
+        assert_eq!(
+            infer(&folder_first).unwrap().1,
+            ManufacturerSource::ParentFolder
+        );
+
+        let id_next = plugin("/VST3/Nova.vst3", Some("com.acme.nova"), copyright);
+        assert_eq!(
+            infer(&id_next),
+            Some(("Acme".to_string(), ManufacturerSource::BundleIdentifier))
+        );
+
+        let copyright_last = plugin("/VST3/Nova.vst3", Some("com.juce.nova"), copyright);
+        assert_eq!(
+            infer(&copyright_last),
+            Some(("Tokyo Dawn Labs".to_string(), ManufacturerSource::Copyright))
+        );
+    }
+}
@@ -1,5 +1,25 @@
+pub mod aliases;
+pub mod duplicates;
+pub mod formats;
+pub mod inference;
+pub mod overrides;
+pub mod profiles;
+pub mod protection;
+pub mod rules;
 pub mod types;
+pub mod updates;
 pub mod utils;
+pub mod version;
 
-pub use types::{Plugin, PluginType};
-pub use utils::clean_manufacturer_name;
+pub use aliases::VendorAliases;
+pub use duplicates::{find_duplicates, DuplicateSet};
+pub use formats::{find_redundant_formats, FormatPolicy, RedundantFormat};
+pub use inference::{infer_manufacturer, ManufacturerSource};
+pub use overrides::VendorOverrides;
+pub use profiles::{plugin_identity, Profile, Profiles, SavedSelections};
+pub use protection::ProtectList;
+pub use rules::{Rule, RuleContext, RULE_HELP};
+pub use types::{CodeSignature, Plugin, PluginType, SignatureStatus};
+pub use updates::{ManifestEntry, UpdateFeed, UpdateManifest};
+pub use utils::{clean_manufacturer_name, normalize_name};
+pub use version::find_version_mismatches;
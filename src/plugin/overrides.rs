@@ -0,0 +1,173 @@
+use super::inference::ManufacturerSource;
+use super::profiles::plugin_identity;
+use super::types::Plugin;
+use crate::utils::config::{load_json, save_json};
+use crate::utils::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const VENDOR_OVERRIDES_FILE: &str = "vendor_overrides.json";
+
+/// Vendor corrections made by hand in the UI, reapplied after the automatic grouping
+/// on every scan.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VendorOverrides {
+    /// Whole groups renamed or merged into another, keyed by the automatic group name.
+    /// A group renamed back to its automatic name has no entry.
+    #[serde(default)]
+    pub renames: BTreeMap<String, String>,
+    /// Plugins moved to another vendor, keyed by bundle identifier so every format of
+    /// the product moves together.
+    #[serde(default)]
+    pub by_bundle_id: BTreeMap<String, String>,
+    /// Plugins moved to another vendor that have no bundle identifier, keyed by
+    /// `plugin_identity` so the move survives disabling and enabling the plugin.
+    #[serde(default)]
+    pub by_identity: BTreeMap<String, String>,
+}
+
+impl VendorOverrides {
+    pub fn load() -> Self {
+        load_json(VENDOR_OVERRIDES_FILE)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        save_json(VENDOR_OVERRIDES_FILE, self)
+    }
+
+    pub fn assign_plugin(&mut self, plugin: &Plugin, vendor: &str) {
+        match &plugin.bundle_id {
+            Some(id) => {
+                self.by_bundle_id.insert(id.clone(), vendor.to_string());
+            }
+            None => {
+                self.by_identity
+                    .insert(plugin_identity(plugin), vendor.to_string());
+            }
+        }
+    }
+
+    /// Renames a group, or merges it into another when `to` already exists. `from` is
+    /// the name the group is shown under, which may itself be the target of a rename.
+    pub fn rename_group(&mut self, from: &str, to: &str) {
+        if from == to {
+            return;
+        }
+        let to = to.to_string();
+
+        for target in self
+            .renames
+            .values_mut()
+            .chain(self.by_bundle_id.values_mut())
+            .chain(self.by_identity.values_mut())
+        {
+            if target == from {
+                *target = to.clone();
+            }
+        }
+        self.renames.insert(from.to_string(), to);
+        self.renames.retain(|from, to| from != to);
+    }
+
+    fn plugin_vendor(&self, plugin: &Plugin) -> Option<&String> {
+        match &plugin.bundle_id {
+            Some(id) => self.by_bundle_id.get(id),
+            None => self.by_identity.get(&plugin_identity(plugin)),
+        }
+    }
+
+    pub fn apply(&self, groups: BTreeMap<String, Vec<Plugin>>) -> BTreeMap<String, Vec<Plugin>> {
+        let mut regrouped: BTreeMap<String, Vec<Plugin>> = BTreeMap::new();
+
+        for (vendor, plugins) in groups {
+            let renamed = self.renames.get(&vendor);
+            for mut plugin in plugins {
+                if let Some(target) = self.plugin_vendor(&plugin).or(renamed) {
+                    plugin.manufacturer = target.clone();
+                    plugin.manufacturer_source = ManufacturerSource::Manual;
+                }
+                regrouped
+                    .entry(plugin.manufacturer.clone())
+                    .or_default()
+                    .push(plugin);
+            }
+        }
+
+        for group in regrouped.values_mut() {
+            group.sort_by_key(|p| p.name.to_lowercase());
+        }
+        regrouped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::PluginType;
+
+    fn plugin(name: &str, manufacturer: &str, path: &str) -> Plugin {
+        Plugin {
+            path: path.into(),
+            ..Plugin::test(name, manufacturer, PluginType::VST3)
+        }
+    }
+
+    fn groups(plugins: &[Plugin]) -> BTreeMap<String, Vec<Plugin>> {
+        let mut groups: BTreeMap<String, Vec<Plugin>> = BTreeMap::new();
+        for plugin in plugins {
+            groups
+                .entry(plugin.manufacturer.clone())
+                .or_default()
+                .push(plugin.clone());
+        }
+        groups
+    }
+
+    fn names(groups: &BTreeMap<String, Vec<Plugin>>) -> Vec<&str> {
+        groups.keys().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn renaming_back_restores_the_automatic_name() {
+        let plugins = [plugin("Synth", "Foo", "/vst3/Synth.vst3")];
+        let mut overrides = VendorOverrides::default();
+
+        overrides.rename_group("Foo", "Baz");
+        assert_eq!(names(&overrides.apply(groups(&plugins))), ["Baz"]);
+
+        overrides.rename_group("Baz", "Foo");
+        let regrouped = overrides.apply(groups(&plugins));
+        assert_eq!(names(&regrouped), ["Foo"]);
+        assert!(!overrides.renames.contains_key("Foo"));
+    }
+
+    #[test]
+    fn merging_into_a_renamed_group() {
+        let plugins = [
+            plugin("Synth", "Foo", "/vst3/Synth.vst3"),
+            plugin("Reverb", "Bar", "/vst3/Reverb.vst3"),
+        ];
+        let mut overrides = VendorOverrides::default();
+
+        overrides.rename_group("Foo", "Baz");
+        overrides.rename_group("Bar", "Baz");
+        assert_eq!(names(&overrides.apply(groups(&plugins))), ["Baz"]);
+
+        overrides.rename_group("Baz", "Qux");
+        let regrouped = overrides.apply(groups(&plugins));
+        assert_eq!(names(&regrouped), ["Qux"]);
+        assert_eq!(regrouped["Qux"].len(), 2);
+    }
+
+    #[test]
+    fn reassignment_survives_disabling() {
+        let enabled = plugin("Synth", "Foo", "/vst3/Synth.vst3");
+        let mut overrides = VendorOverrides::default();
+        overrides.assign_plugin(&enabled, "Bar");
+
+        let mut disabled = enabled.clone();
+        disabled.path = "/vst3 (Disabled)/Synth.vst3".into();
+        disabled.disabled = true;
+        assert_eq!(names(&overrides.apply(groups(&[disabled]))), ["Bar"]);
+    }
+}
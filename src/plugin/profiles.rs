@@ -0,0 +1,130 @@
+use super::types::Plugin;
+use crate::utils::config::{load_json, save_json};
+use crate::utils::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+pub const PROFILES_FILE: &str = "profiles.json";
+pub const SELECTIONS_FILE: &str = "selections.json";
+
+/// Identifies a plugin across enabling and disabling, which change its path: the
+/// format plus the bundle identifier, or the file name when there's none.
+pub fn plugin_identity(plugin: &Plugin) -> String {
+    let id = plugin.bundle_id.clone().unwrap_or_else(|| {
+        plugin
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_lowercase()
+    });
+    format!("{:?}:{}", plugin.plugin_type, id)
+}
+
+/// A named set of plugins to keep enabled; everything else is disabled while the
+/// profile is active.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    pub plugins: BTreeSet<String>,
+}
+
+impl Profile {
+    pub fn from_plugins<'a>(plugins: impl IntoIterator<Item = &'a Plugin>) -> Self {
+        Self {
+            plugins: plugins.into_iter().map(plugin_identity).collect(),
+        }
+    }
+
+    pub fn contains(&self, plugin: &Plugin) -> bool {
+        self.plugins.contains(&plugin_identity(plugin))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profiles {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    /// The profile last switched to, if any.
+    #[serde(default)]
+    pub active: Option<String>,
+}
+
+impl Profiles {
+    pub fn load() -> Self {
+        load_json(PROFILES_FILE)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        save_json(PROFILES_FILE, self)
+    }
+}
+
+/// Selections saved by name, as plugin identities so they survive enabling and
+/// disabling.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedSelections {
+    #[serde(default)]
+    pub selections: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl SavedSelections {
+    pub fn load() -> Self {
+        load_json(SELECTIONS_FILE)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        save_json(SELECTIONS_FILE, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::PluginType;
+
+    #[test]
+    fn identity_survives_moving_the_plugin() {
+        let enabled = Plugin {
+            path: "/VST3/Pro-Q 3.vst3".into(),
+            bundle_id: Some("com.fabfilter.Pro-Q.3".to_string()),
+            ..Plugin::test("Pro-Q 3", "FabFilter", PluginType::VST3)
+        };
+        let disabled = Plugin {
+            path: "/VST3/Disabled/Renamed.vst3".into(),
+            disabled: true,
+            ..enabled.clone()
+        };
+        assert_eq!(plugin_identity(&enabled), "VST3:com.fabfilter.Pro-Q.3");
+        assert_eq!(plugin_identity(&enabled), plugin_identity(&disabled));
+    }
+
+    #[test]
+    fn identity_falls_back_to_the_file_name() {
+        let plugin = |path: &str, plugin_type| Plugin {
+            path: path.into(),
+            ..Plugin::test("Serum", "Xfer Records", plugin_type)
+        };
+        let vst2 = plugin("/VST/Serum.dll", PluginType::VST2);
+        assert_eq!(plugin_identity(&vst2), "VST2:serum.dll");
+        assert_eq!(
+            plugin_identity(&vst2),
+            plugin_identity(&plugin("/VST/Disabled/SERUM.dll", PluginType::VST2))
+        );
+        assert_ne!(
+            plugin_identity(&vst2),
+            plugin_identity(&plugin("/VST/Serum.dll", PluginType::VST3))
+        );
+    }
+
+    #[test]
+    fn profiles_match_by_identity() {
+        let pro_q = Plugin::test("Pro-Q 3", "FabFilter", PluginType::VST3);
+        let profile = Profile::from_plugins([&pro_q]);
+        let moved = Plugin {
+            path: "/plugins/Disabled/Pro-Q 3.VST3".into(),
+            ..pro_q.clone()
+        };
+        assert!(profile.contains(&moved));
+        assert!(!profile.contains(&Plugin::test("Pro-Q 3", "FabFilter", PluginType::AU)));
+    }
+}
@@ -0,0 +1,246 @@
+use super::types::Plugin;
+use crate::utils::config::{save_json, try_load_json};
+use crate::utils::error::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+pub const PROTECT_LIST_FILE: &str = "protected.json";
+
+/// Plugins that must never be removed or moved, matched by vendor, bundle identifier
+/// or a glob over the plugin's path such as `/Library/Audio/Plug-Ins/VST3/Waves/**`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProtectList {
+    #[serde(default)]
+    pub vendors: BTreeSet<String>,
+    #[serde(default)]
+    pub bundle_ids: BTreeSet<String>,
+    /// Call `compile_globs` after changing these directly.
+    #[serde(default)]
+    pub path_globs: BTreeSet<String>,
+    /// `path_globs` compiled once, since every plugin is checked on every frame.
+    #[serde(skip)]
+    compiled_globs: Vec<(String, Regex)>,
+}
+
+impl ProtectList {
+    /// Fails when the file exists but can't be read, rather than starting over with an
+    /// empty list that a later save would write over it.
+    pub fn load() -> Result<Self> {
+        let mut list: Self = try_load_json(PROTECT_LIST_FILE)?;
+        list.compile_globs();
+        Ok(list)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        save_json(PROTECT_LIST_FILE, self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vendors.is_empty() && self.bundle_ids.is_empty() && self.path_globs.is_empty()
+    }
+
+    /// The rule that protects `plugin`, described for a tooltip.
+    pub fn rule_for(&self, plugin: &Plugin) -> Option<String> {
+        if let Some(vendor) = self
+            .vendors
+            .iter()
+            .find(|v| v.eq_ignore_ascii_case(&plugin.manufacturer))
+        {
+            return Some(format!("Vendor {} is protected", vendor));
+        }
+        if let Some(id) = plugin.bundle_id.as_ref().filter(|id| {
+            self.bundle_ids
+                .iter()
+                .any(|protected| protected.eq_ignore_ascii_case(id))
+        }) {
+            return Some(format!("Bundle ID {} is protected", id));
+        }
+        self.glob_for(&plugin.path)
+            .map(|glob| format!("Path matches {}", glob))
+    }
+
+    pub fn protects(&self, plugin: &Plugin) -> bool {
+        self.rule_for(plugin).is_some()
+    }
+
+    /// Whether a path that isn't a scanned plugin, such as a package's file, is
+    /// covered by one of the path globs.
+    pub fn protects_path(&self, path: &Path) -> bool {
+        self.glob_for(path).is_some()
+    }
+
+    fn glob_for(&self, path: &Path) -> Option<&String> {
+        let path = path.to_string_lossy();
+        self.compiled_globs
+            .iter()
+            .find(|(_, re)| re.is_match(&path))
+            .map(|(glob, _)| glob)
+    }
+
+    /// Compiles `path_globs`, skipping any that don't make a valid pattern.
+    pub fn compile_globs(&mut self) {
+        self.compiled_globs = self
+            .path_globs
+            .iter()
+            .filter_map(|glob| Some((glob.clone(), glob_regex(glob)?)))
+            .collect();
+    }
+
+    /// Protects one plugin by its bundle identifier, so every format of the product is
+    /// covered, or by its exact path when it has none.
+    pub fn protect_plugin(&mut self, plugin: &Plugin) {
+        match &plugin.bundle_id {
+            Some(id) => {
+                self.bundle_ids.insert(id.clone());
+            }
+            None => {
+                self.path_globs.insert(escape_glob(&plugin.path));
+                self.compile_globs();
+            }
+        }
+    }
+
+    /// Removes every rule that protects `plugin`.
+    pub fn unprotect_plugin(&mut self, plugin: &Plugin) {
+        self.vendors
+            .retain(|v| !v.eq_ignore_ascii_case(&plugin.manufacturer));
+        if let Some(id) = &plugin.bundle_id {
+            self.bundle_ids.retain(|p| !p.eq_ignore_ascii_case(id));
+        }
+        while let Some(glob) = self.glob_for(&plugin.path).cloned() {
+            self.path_globs.remove(&glob);
+            self.compile_globs();
+        }
+    }
+}
+
+/// `**` matches across folders, `*` and `?` within one. Matching ignores case, since
+/// plugin folders on macOS and Windows do.
+fn glob_regex(glob: &str) -> Option<Regex> {
+    let mut pattern = String::from("(?i)^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                pattern.push_str(".*");
+            }
+            '*' => pattern.push_str(r"[^/\\]*"),
+            '?' => pattern.push_str(r"[^/\\]"),
+            '/' | '\\' => pattern.push_str(r"[/\\]"),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).ok()
+}
+
+/// A glob matching exactly `path`. Wildcard characters in the path itself are left to
+/// match any single character, which still includes themselves.
+fn escape_glob(path: &Path) -> String {
+    path.to_string_lossy().replace('*', "?")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::PluginType;
+
+    fn globs(globs: &[&str]) -> ProtectList {
+        let mut list = ProtectList {
+            path_globs: globs.iter().map(|g| g.to_string()).collect(),
+            ..ProtectList::default()
+        };
+        list.compile_globs();
+        list
+    }
+
+    fn protects(list: &ProtectList, path: &str) -> bool {
+        list.protects_path(Path::new(path))
+    }
+
+    #[test]
+    fn single_star_stays_within_a_folder() {
+        let list = globs(&["/VST3/Waves/*.vst3"]);
+        assert!(protects(&list, "/VST3/Waves/CLA-76.vst3"));
+        assert!(!protects(&list, "/VST3/Waves/Sub/CLA-76.vst3"));
+        assert!(!protects(&list, "/VST3/Waves/CLA-76.vst3/Contents"));
+    }
+
+    #[test]
+    fn double_star_crosses_folders() {
+        let list = globs(&["/VST3/Waves/**"]);
+        assert!(protects(&list, "/VST3/Waves/CLA-76.vst3"));
+        assert!(protects(&list, "/VST3/Waves/Sub/CLA-76.vst3/Contents"));
+        assert!(!protects(&list, "/VST3/Waves"));
+        assert!(!protects(&list, "/VST3/WavesShell.vst3"));
+    }
+
+    #[test]
+    fn question_mark_matches_one_character() {
+        let list = globs(&["/VST3/Pro-?? 3.vst3"]);
+        assert!(protects(&list, "/VST3/Pro-Q? 3.vst3"));
+        assert!(protects(&list, "/VST3/Pro-MB 3.vst3"));
+        assert!(!protects(&list, "/VST3/Pro-Q 3.vst3"));
+        assert!(!protects(&list, "/VST3/Pro-/Q 3.vst3"));
+    }
+
+    #[test]
+    fn matching_ignores_case_and_separator_style() {
+        let list = globs(&[r"C:\Program Files\Common Files\VST3\**"]);
+        assert!(protects(
+            &list,
+            r"c:\program files\common files\vst3\Synth.vst3"
+        ));
+        assert!(protects(
+            &list,
+            "C:/Program Files/Common Files/VST3/Synth.vst3"
+        ));
+        assert!(!protects(&list, r"C:\Program Files\VST3\Synth.vst3"));
+    }
+
+    #[test]
+    fn regex_characters_are_literal() {
+        let list = globs(&["/VST3/Synth (x64).vst3"]);
+        assert!(protects(&list, "/VST3/Synth (x64).vst3"));
+        assert!(!protects(&list, "/VST3/Synth x64.vst3"));
+    }
+
+    #[test]
+    fn vendor_and_bundle_id_rules() {
+        let mut list = ProtectList::default();
+        list.vendors.insert("Waves".to_string());
+        list.bundle_ids.insert("com.fabfilter.Pro-Q.3".to_string());
+
+        let waves = Plugin::test("CLA-76", "WAVES", PluginType::VST3);
+        assert_eq!(
+            list.rule_for(&waves).as_deref(),
+            Some("Vendor Waves is protected")
+        );
+
+        let pro_q = Plugin {
+            bundle_id: Some("COM.FABFILTER.PRO-Q.3".to_string()),
+            ..Plugin::test("Pro-Q 3", "FabFilter", PluginType::AU)
+        };
+        assert!(list.protects(&pro_q));
+        assert!(!list.protects(&Plugin::test("Pro-C 2", "FabFilter", PluginType::AU)));
+    }
+
+    #[test]
+    fn protecting_and_unprotecting_a_plugin_without_an_id() {
+        let mut list = ProtectList::default();
+        let plugin = Plugin {
+            path: "/VST3/Odd*Name.vst3".into(),
+            ..Plugin::test("Odd*Name", "Someone", PluginType::VST3)
+        };
+        list.protect_plugin(&plugin);
+        assert!(list.protects(&plugin));
+        assert!(!protects(&list, "/VST3/OddlyNamed.vst3"));
+
+        list.unprotect_plugin(&plugin);
+        assert!(!list.protects(&plugin));
+        assert!(list.is_empty());
+    }
+}
@@ -0,0 +1,377 @@
+use super::types::{Plugin, PluginType};
+use super::utils::normalize_name;
+use super::version::Version;
+use crate::utils::error::Result;
+use anyhow::{anyhow, bail};
+use std::collections::HashSet;
+use std::time::{Duration, SystemTime};
+
+/// Describes the syntax for tooltips and the command line.
+pub const RULE_HELP: &str = "Terms: format:vst2|vst3|au|aax, has-format:FORMAT, vendor:NAME, \
+name:TEXT, older-than:VERSION, unmodified:2y|6m|30d, disabled, all. \
+Combine them with and, or, not and parentheses; quote names with spaces, \
+e.g. format:vst2 and has-format:vst3";
+
+/// A selection rule, such as `vendor:"Native Instruments" and older-than:2.0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule {
+    All,
+    Format(PluginType),
+    /// Another copy of the same product is installed and enabled in this format.
+    HasFormat(PluginType),
+    Vendor(String),
+    /// The name contains this text.
+    Name(String),
+    OlderThan(Version),
+    /// Nothing in the plugin has been modified for this long.
+    UnmodifiedFor(Duration),
+    Disabled,
+    Not(Box<Rule>),
+    And(Box<Rule>, Box<Rule>),
+    Or(Box<Rule>, Box<Rule>),
+}
+
+/// What rules are evaluated against besides the plugin itself.
+pub struct RuleContext {
+    /// Normalized vendor, normalized name and format of every enabled plugin.
+    products: HashSet<(String, String, PluginType)>,
+    now: SystemTime,
+}
+
+impl RuleContext {
+    pub fn new<'a>(plugins: impl IntoIterator<Item = &'a Plugin>) -> Self {
+        Self {
+            products: plugins
+                .into_iter()
+                .filter(|p| !p.disabled)
+                .map(|p| product_key(p, p.plugin_type.clone()))
+                .collect(),
+            now: SystemTime::now(),
+        }
+    }
+}
+
+fn product_key(plugin: &Plugin, format: PluginType) -> (String, String, PluginType) {
+    (
+        normalize_name(&plugin.manufacturer),
+        normalize_name(&plugin.name),
+        format,
+    )
+}
+
+impl Rule {
+    pub fn parse(text: &str) -> Result<Rule> {
+        let tokens = tokenize(text)?;
+        if tokens.is_empty() {
+            bail!("The rule is empty");
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let rule = parser.or()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            bail!("Unexpected {:?}", token);
+        }
+        Ok(rule)
+    }
+
+    pub fn matches(&self, plugin: &Plugin, context: &RuleContext) -> bool {
+        match self {
+            Rule::All => true,
+            Rule::Format(format) => plugin.plugin_type == *format,
+            Rule::HasFormat(format) => {
+                plugin.plugin_type != *format
+                    && context
+                        .products
+                        .contains(&product_key(plugin, format.clone()))
+            }
+            Rule::Vendor(vendor) => normalize_name(&plugin.manufacturer) == normalize_name(vendor),
+            Rule::Name(text) => plugin.name.to_lowercase().contains(&text.to_lowercase()),
+            Rule::OlderThan(version) => plugin.parsed_version().is_some_and(|v| v < *version),
+            Rule::UnmodifiedFor(age) => last_modified(plugin).is_some_and(|modified| {
+                context
+                    .now
+                    .duration_since(modified)
+                    .is_ok_and(|elapsed| elapsed >= *age)
+            }),
+            Rule::Disabled => plugin.disabled,
+            Rule::Not(rule) => !rule.matches(plugin, context),
+            Rule::And(a, b) => a.matches(plugin, context) && b.matches(plugin, context),
+            Rule::Or(a, b) => a.matches(plugin, context) || b.matches(plugin, context),
+        }
+    }
+}
+
+/// The newest modification time of anything in the bundle.
+fn last_modified(plugin: &Plugin) -> Option<SystemTime> {
+    walkdir::WalkDir::new(&plugin.path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .max()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Word(String),
+}
+
+/// Splits on whitespace and parentheses. Double quotes keep spaces in a value, as in
+/// `vendor:"Native Instruments"`.
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            _ => {
+                let mut word = String::new();
+                let mut quoted = false;
+                while let Some(&c) = chars.peek() {
+                    if !quoted && (c.is_whitespace() || c == '(' || c == ')') {
+                        break;
+                    }
+                    chars.next();
+                    if c == '"' {
+                        quoted = !quoted;
+                    } else {
+                        word.push(c);
+                    }
+                }
+                if quoted {
+                    bail!("Unclosed quote in {:?}", word);
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(
+            self.tokens.get(self.pos),
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword)
+        );
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Rule> {
+        let mut rule = self.and()?;
+        while self.keyword("or") {
+            rule = Rule::Or(Box::new(rule), Box::new(self.and()?));
+        }
+        Ok(rule)
+    }
+
+    fn and(&mut self) -> Result<Rule> {
+        let mut rule = self.unary()?;
+        while self.keyword("and") {
+            rule = Rule::And(Box::new(rule), Box::new(self.unary()?));
+        }
+        Ok(rule)
+    }
+
+    fn unary(&mut self) -> Result<Rule> {
+        if self.keyword("not") {
+            return Ok(Rule::Not(Box::new(self.unary()?)));
+        }
+
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| anyhow!("The rule ends too early"))?;
+        self.pos += 1;
+        match token {
+            Token::Open => {
+                let rule = self.or()?;
+                if self.tokens.get(self.pos) != Some(&Token::Close) {
+                    bail!("Missing )");
+                }
+                self.pos += 1;
+                Ok(rule)
+            }
+            Token::Close => bail!("Unexpected )"),
+            Token::Word(word) => term(&word),
+        }
+    }
+}
+
+fn term(word: &str) -> Result<Rule> {
+    let (key, value) = word.split_once(':').unwrap_or((word, ""));
+    let key = key.to_lowercase();
+    let rule = match (key.as_str(), value) {
+        ("all", "") => Rule::All,
+        ("disabled", "") => Rule::Disabled,
+        ("format", value) => Rule::Format(parse_format(value)?),
+        ("has-format", value) => Rule::HasFormat(parse_format(value)?),
+        ("vendor", value) if !value.is_empty() => Rule::Vendor(value.to_string()),
+        ("name", value) if !value.is_empty() => Rule::Name(value.to_string()),
+        ("older-than", value) => Rule::OlderThan(
+            Version::parse(value).ok_or_else(|| anyhow!("Expected a version, not {:?}", value))?,
+        ),
+        ("unmodified", value) => Rule::UnmodifiedFor(parse_age(value)?),
+        _ => bail!("Unknown term {:?}", word),
+    };
+    Ok(rule)
+}
+
+fn parse_format(value: &str) -> Result<PluginType> {
+    match value.to_lowercase().as_str() {
+        "vst2" | "vst" => Ok(PluginType::VST2),
+        "vst3" => Ok(PluginType::VST3),
+        "au" | "component" => Ok(PluginType::AU),
+        "aax" => Ok(PluginType::AAX),
+        _ => bail!("Unknown format {:?}; use vst2, vst3, au or aax", value),
+    }
+}
+
+/// `30d`, `6m` or `2y`.
+fn parse_age(value: &str) -> Result<Duration> {
+    let invalid = || anyhow!("Expected an age like 30d, 6m or 2y, not {:?}", value);
+    let (split, unit) = value.char_indices().last().ok_or_else(invalid)?;
+    let count: u64 = value[..split].parse().map_err(|_| invalid())?;
+    let days_per_unit = match unit {
+        'd' => 1,
+        'm' => 30,
+        'y' => 365,
+        _ => return Err(invalid()),
+    };
+    let seconds = count
+        .checked_mul(days_per_unit)
+        .and_then(|days| days.checked_mul(86400))
+        .ok_or_else(|| anyhow!("{:?} is too long an age", value))?;
+    Ok(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Rule {
+        Rule::parse(text).unwrap()
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            parse("disabled or format:vst2 and has-format:vst3"),
+            Rule::Or(
+                Box::new(Rule::Disabled),
+                Box::new(Rule::And(
+                    Box::new(Rule::Format(PluginType::VST2)),
+                    Box::new(Rule::HasFormat(PluginType::VST3)),
+                )),
+            )
+        );
+    }
+
+    #[test]
+    fn not_and_parentheses() {
+        assert_eq!(
+            parse("not (disabled or all)"),
+            Rule::Not(Box::new(Rule::Or(
+                Box::new(Rule::Disabled),
+                Box::new(Rule::All)
+            )))
+        );
+        assert_eq!(
+            parse("NOT disabled AND all"),
+            Rule::And(
+                Box::new(Rule::Not(Box::new(Rule::Disabled))),
+                Box::new(Rule::All)
+            )
+        );
+    }
+
+    #[test]
+    fn quotes_keep_spaces() {
+        assert_eq!(
+            parse(r#"vendor:"Native Instruments""#),
+            Rule::Vendor("Native Instruments".to_string())
+        );
+        assert_eq!(
+            parse(r#"name:"Pro (Q)" or all"#),
+            Rule::Or(
+                Box::new(Rule::Name("Pro (Q)".to_string())),
+                Box::new(Rule::All)
+            )
+        );
+    }
+
+    #[test]
+    fn malformed_rules_are_errors() {
+        for text in [
+            "",
+            r#"vendor:"Native"#,
+            "(all",
+            "all)",
+            "all and",
+            "format:clap",
+            "vendor:",
+            "older-than:beta",
+            "sideways",
+        ] {
+            assert!(Rule::parse(text).is_err(), "{:?} should not parse", text);
+        }
+    }
+
+    fn plugin(format: PluginType, disabled: bool) -> Plugin {
+        Plugin {
+            disabled,
+            ..Plugin::test("Pro-Q 3", "FabFilter", format)
+        }
+    }
+
+    #[test]
+    fn has_format_ignores_disabled_copies() {
+        let rule = parse("format:vst2 and has-format:vst3");
+        let vst2 = plugin(PluginType::VST2, false);
+
+        let enabled = [vst2.clone(), plugin(PluginType::VST3, false)];
+        assert!(rule.matches(&vst2, &RuleContext::new(&enabled)));
+
+        let disabled = [vst2.clone(), plugin(PluginType::VST3, true)];
+        assert!(!rule.matches(&vst2, &RuleContext::new(&disabled)));
+    }
+
+    #[test]
+    fn ages() {
+        assert_eq!(parse_age("30d").unwrap(), Duration::from_secs(30 * 86400));
+        assert_eq!(parse_age("6m").unwrap(), Duration::from_secs(180 * 86400));
+        assert_eq!(parse_age("2y").unwrap(), Duration::from_secs(730 * 86400));
+        for bad in [
+            "",
+            "d",
+            "2",
+            "2w",
+            "-2d",
+            "2é",
+            "é",
+            "18446744073709551615y",
+        ] {
+            assert!(parse_age(bad).is_err(), "{:?} should not parse", bad);
+        }
+    }
+}
@@ -1,7 +1,8 @@
+use super::inference::ManufacturerSource;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum PluginType {
     VST2,
     VST3,
@@ -9,11 +10,120 @@ pub enum PluginType {
     AAX,
 }
 
+impl PluginType {
+    pub const ALL: [PluginType; 4] = [
+        PluginType::VST2,
+        PluginType::VST3,
+        PluginType::AU,
+        PluginType::AAX,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignatureStatus {
+    Unsigned,
+    AdHoc,
+    Signed,
+    Notarized,
+}
+
+impl SignatureStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SignatureStatus::Unsigned => "Unsigned",
+            SignatureStatus::AdHoc => "Ad-hoc signed",
+            SignatureStatus::Signed => "Signed",
+            SignatureStatus::Notarized => "Notarized",
+        }
+    }
+
+    /// Whether the signature says nothing about who built the plugin.
+    pub fn is_anonymous(&self) -> bool {
+        matches!(self, SignatureStatus::Unsigned | SignatureStatus::AdHoc)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeSignature {
+    pub status: SignatureStatus,
+    /// Common name of the signing certificate, e.g. "Developer ID Application: Foo (TEAMID)".
+    pub signer: Option<String>,
+    pub organization: Option<String>,
+    pub team_id: Option<String>,
+    pub identifier: Option<String>,
+    /// Signing certificate validity as `YYYY-MM-DD`.
+    pub valid_from: Option<String>,
+    pub valid_until: Option<String>,
+}
+
+impl CodeSignature {
+    pub fn unsigned() -> Self {
+        Self {
+            status: SignatureStatus::Unsigned,
+            signer: None,
+            organization: None,
+            team_id: None,
+            identifier: None,
+            valid_from: None,
+            valid_until: None,
+        }
+    }
+
+    /// The company the certificate was issued to, taken from its organization or,
+    /// failing that, from a "Developer ID Application: Name (TEAMID)" common name.
+    pub fn publisher(&self) -> Option<String> {
+        self.organization.clone().or_else(|| {
+            let signer = self.signer.as_deref()?;
+            let name = signer.split_once(": ").map_or(signer, |(_, name)| name);
+            let name = match name.rfind(" (") {
+                Some(i) if name.ends_with(')') => &name[..i],
+                _ => name,
+            };
+            let name = name.trim();
+            (!name.is_empty()).then(|| name.to_string())
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Plugin {
     pub name: String,
     pub manufacturer: String,
+    pub manufacturer_source: ManufacturerSource,
     pub version: Option<String>,
+    pub bundle_id: Option<String>,
+    /// The four-character AudioComponent manufacturer code, for bundles that declare one.
+    pub manufacturer_code: Option<String>,
+    pub copyright: Option<String>,
+    /// None when the platform's signature format isn't inspected.
+    pub signature: Option<CodeSignature>,
     pub path: PathBuf,
     pub plugin_type: PluginType,
+    /// On-disk size in bytes of the plugin file or the whole bundle.
+    pub size: u64,
+    /// Parked in the format folder's "(Disabled)" sibling, where DAWs don't look.
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+#[cfg(test)]
+impl Plugin {
+    /// An enabled plugin with nothing but a name, vendor and format, stored at
+    /// `/plugins/<name>.<format>`. Tests fill in anything else with struct update syntax.
+    pub fn test(name: &str, manufacturer: &str, plugin_type: PluginType) -> Plugin {
+        Plugin {
+            name: name.to_string(),
+            manufacturer: manufacturer.to_string(),
+            manufacturer_source: ManufacturerSource::Metadata,
+            version: None,
+            bundle_id: None,
+            manufacturer_code: None,
+            copyright: None,
+            signature: None,
+            path: format!("/plugins/{}.{:?}", name, plugin_type).into(),
+            plugin_type,
+            size: 0,
+            disabled: false,
+        }
+    }
 }
@@ -0,0 +1,170 @@
+use super::types::Plugin;
+use super::utils::normalize_name;
+use super::version::Version;
+use crate::utils::config::{load_json, save_json};
+use crate::utils::error::Result;
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const UPDATE_FEED_FILE: &str = "update_feed.json";
+
+/// The newest manifest schema this build understands; see `docs/update-manifest.md`.
+pub const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A list of the latest release of each product, published by a vendor or kept by
+/// hand.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateManifest {
+    pub schema_version: u32,
+    #[serde(default)]
+    pub products: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub vendor: String,
+    pub product: String,
+    pub latest_version: String,
+    #[serde(default)]
+    pub download_url: Option<String>,
+}
+
+impl UpdateManifest {
+    pub fn parse(content: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Header {
+            schema_version: u32,
+        }
+
+        let header: Header = serde_json::from_str(content).context("Not an update manifest")?;
+        if header.schema_version > MANIFEST_SCHEMA_VERSION {
+            bail!(
+                "The manifest uses schema version {}, but this app only reads up to {}",
+                header.schema_version,
+                MANIFEST_SCHEMA_VERSION
+            );
+        }
+        Ok(serde_json::from_str(content)?)
+    }
+
+    /// Reads a manifest from an `http://` or `https://` URL, or from a file path
+    /// (optionally written as a `file://` URL). Downloads give up after a short
+    /// timeout rather than waiting on an unresponsive server.
+    pub fn load(source: &str) -> Result<Self> {
+        let source = source.trim();
+        let content = if source.starts_with("http://") || source.starts_with("https://") {
+            ureq::AgentBuilder::new()
+                .timeout_connect(CONNECT_TIMEOUT)
+                .timeout_read(READ_TIMEOUT)
+                .build()
+                .get(source)
+                .call()
+                .with_context(|| format!("Failed to download {}", source))?
+                .into_string()?
+        } else {
+            let path = source.strip_prefix("file://").unwrap_or(source);
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?
+        };
+        Self::parse(&content)
+    }
+
+    /// The entry for a plugin's product if it lists a newer version than the one
+    /// installed. Plugins without a readable version are never reported.
+    pub fn update_for(&self, plugin: &Plugin) -> Option<&ManifestEntry> {
+        let installed = plugin.parsed_version()?;
+        let vendor = normalize_name(&plugin.manufacturer);
+        let product = normalize_name(&plugin.name);
+        self.products
+            .iter()
+            .filter(|e| {
+                normalize_name(&e.vendor) == vendor && normalize_name(&e.product) == product
+            })
+            .find(|e| Version::parse(&e.latest_version).is_some_and(|latest| latest > installed))
+    }
+}
+
+/// Where the manifest was last loaded from, so the next check uses it again.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateFeed {
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+impl UpdateFeed {
+    pub fn load() -> Self {
+        load_json(UPDATE_FEED_FILE)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        save_json(UPDATE_FEED_FILE, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::PluginType;
+
+    fn plugin(manufacturer: &str, name: &str, version: Option<&str>) -> Plugin {
+        Plugin {
+            version: version.map(str::to_string),
+            ..Plugin::test(name, manufacturer, PluginType::VST3)
+        }
+    }
+
+    #[test]
+    fn loads_a_manifest_from_a_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("manifest.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "schema_version": 1,
+                "products": [
+                    {
+                        "vendor": "FabFilter",
+                        "product": "Pro-Q 3",
+                        "latest_version": "3.24",
+                        "download_url": "https://example.com/pro-q"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        for source in [
+            path.display().to_string(),
+            format!("file://{}", path.display()),
+        ] {
+            let manifest = UpdateManifest::load(&source).unwrap();
+            assert_eq!(manifest.products.len(), 1);
+
+            let update = manifest.update_for(&plugin("Fab Filter", "Pro Q 3", Some("3.21")));
+            assert_eq!(update.unwrap().latest_version, "3.24");
+            assert!(manifest
+                .update_for(&plugin("FabFilter", "Pro-Q 3", Some("3.24.0")))
+                .is_none());
+            assert!(manifest
+                .update_for(&plugin("FabFilter", "Pro-Q 3", None))
+                .is_none());
+            assert!(manifest
+                .update_for(&plugin("FabFilter", "Pro-C 2", Some("1.0")))
+                .is_none());
+        }
+    }
+
+    #[test]
+    fn newer_schema_versions_are_rejected() {
+        let newer = format!(
+            r#"{{"schema_version": {}, "products": []}}"#,
+            MANIFEST_SCHEMA_VERSION + 1
+        );
+        assert!(UpdateManifest::parse(&newer).is_err());
+        assert!(UpdateManifest::parse(r#"{"schema_version": 1}"#).is_ok());
+        assert!(UpdateManifest::parse(r#"{"products": []}"#).is_err());
+    }
+}
@@ -7,3 +7,12 @@ pub fn clean_manufacturer_name(name: &str) -> String {
     });
     SUFFIX_REGEX.replace_all(name, "").trim().to_string()
 }
+
+/// Lowercases a name and drops everything but letters and digits, so "Pro-Q 3",
+/// "ProQ3" and "pro q 3" all compare equal.
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
@@ -0,0 +1,266 @@
+use super::types::{Plugin, PluginType};
+use super::utils::normalize_name;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A plugin version as vendors write it: `1.2.3`, Windows' `1.2.3.4`, `v2.0`,
+/// `1.2.3 (build 456)`, `1.2b3` or `3.0.1-beta2`.
+///
+/// Trailing zero components don't count, so `1.2` equals `1.2.0`. A pre-release sorts
+/// before the release it leads up to, and a build number only breaks ties.
+#[derive(Debug, Clone)]
+pub struct Version {
+    components: Vec<u64>,
+    /// The pre-release label and its number, such as `("beta", 2)`.
+    pre_release: Option<(String, u64)>,
+    build: Option<u64>,
+    text: String,
+}
+
+impl Version {
+    /// Parses anything that starts with a number, possibly after a `v`.
+    pub fn parse(text: &str) -> Option<Version> {
+        let trimmed = text.trim();
+        let rest = trimmed
+            .strip_prefix(['v', 'V'])
+            .filter(|r| r.starts_with(|c: char| c.is_ascii_digit()))
+            .unwrap_or(trimmed);
+
+        let numeric_end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let components: Vec<u64> = rest[..numeric_end]
+            .split('.')
+            .map_while(|s| s.parse().ok())
+            .collect();
+        if components.is_empty() {
+            return None;
+        }
+
+        let suffix = rest[numeric_end..].to_lowercase();
+        let tag = suffix.trim_start_matches([' ', '(', '-', '_']);
+        let pre_release = ["alpha", "beta", "rc", "a", "b"]
+            .into_iter()
+            .filter(|_| !tag.starts_with("build"))
+            .find_map(|label| {
+                let after = tag.strip_prefix(label)?;
+                if after.starts_with(|c: char| c.is_alphabetic()) {
+                    return None;
+                }
+                let number = leading_number(after.trim_start_matches(['.', ' ']));
+                Some((normalize_label(label).to_string(), number.unwrap_or(0)))
+            });
+        let build = match suffix.split_once("build") {
+            Some((_, after)) => leading_number(after.trim_start_matches([' ', '#', ':'])),
+            None => suffix
+                .trim_start()
+                .strip_prefix('(')
+                .and_then(leading_number),
+        };
+
+        Some(Version {
+            components,
+            pre_release,
+            build,
+            text: trimmed.to_string(),
+        })
+    }
+
+    fn significant_components(&self) -> &[u64] {
+        let len = self
+            .components
+            .iter()
+            .rposition(|c| *c != 0)
+            .map_or(0, |i| i + 1);
+        &self.components[..len]
+    }
+}
+
+fn normalize_label(label: &str) -> &str {
+    match label {
+        "a" => "alpha",
+        "b" => "beta",
+        other => other,
+    }
+}
+
+fn leading_number(text: &str) -> Option<u64> {
+    text.split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Pre-release labels in the order releases go through them.
+fn label_rank(label: &str) -> u8 {
+    match label {
+        "alpha" => 0,
+        "beta" => 1,
+        _ => 2,
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.significant_components()
+            .cmp(other.significant_components())
+            .then_with(|| match (&self.pre_release, &other.pre_release) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some((a, an)), Some((b, bn))) => {
+                    label_rank(a).cmp(&label_rank(b)).then(an.cmp(bn))
+                }
+            })
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl Plugin {
+    /// The parsed version, or None when there's no version or it isn't a number.
+    pub fn parsed_version(&self) -> Option<Version> {
+        self.version.as_deref().and_then(Version::parse)
+    }
+}
+
+/// Finds products installed in several formats whose copies aren't all on the same
+/// version, which usually means one installer was skipped during an update. Returns
+/// each copy with the versions of its product's other formats. Disabled copies are
+/// left out, since no host loads them.
+pub fn find_version_mismatches<'a>(
+    plugins: impl IntoIterator<Item = &'a Plugin>,
+) -> Vec<(&'a Plugin, Vec<(PluginType, Version)>)> {
+    let mut products: BTreeMap<(String, String), Vec<(&Plugin, Version)>> = BTreeMap::new();
+    for plugin in plugins.into_iter().filter(|p| !p.disabled) {
+        if let Some(version) = plugin.parsed_version() {
+            products
+                .entry((
+                    normalize_name(&plugin.manufacturer),
+                    normalize_name(&plugin.name),
+                ))
+                .or_default()
+                .push((plugin, version));
+        }
+    }
+
+    let mut mismatches = Vec::new();
+    for copies in products.into_values() {
+        let differs = copies.iter().any(|(a, av)| {
+            copies
+                .iter()
+                .any(|(b, bv)| a.plugin_type != b.plugin_type && av != bv)
+        });
+        if !differs {
+            continue;
+        }
+        for (plugin, _) in &copies {
+            let others = copies
+                .iter()
+                .filter(|(other, _)| other.plugin_type != plugin.plugin_type)
+                .map(|(other, version)| (other.plugin_type.clone(), version.clone()))
+                .collect();
+            mismatches.push((*plugin, others));
+        }
+    }
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Text, components, pre-release and build.
+    type Case<'a> = (&'a str, &'a [u64], Option<(&'a str, u64)>, Option<u64>);
+
+    fn v(text: &str) -> Version {
+        Version::parse(text).unwrap_or_else(|| panic!("{:?} should parse", text))
+    }
+
+    #[test]
+    fn parses_vendor_formats() {
+        let cases: [Case; 9] = [
+            ("1.2.3", &[1, 2, 3], None, None),
+            ("1.2.3.4", &[1, 2, 3, 4], None, None),
+            (" v2.0 ", &[2, 0], None, None),
+            ("V10", &[10], None, None),
+            ("1.2.3 (build 456)", &[1, 2, 3], None, Some(456)),
+            ("1.2 (789)", &[1, 2], None, Some(789)),
+            ("1.2b3", &[1, 2], Some(("beta", 3)), None),
+            ("3.0.1-beta2", &[3, 0, 1], Some(("beta", 2)), None),
+            ("2.0 RC", &[2, 0], Some(("rc", 0)), None),
+        ];
+        for (text, components, pre_release, build) in cases {
+            let version = v(text);
+            assert_eq!(version.components, components, "{}", text);
+            assert_eq!(
+                version.pre_release,
+                pre_release.map(|(label, n)| (label.to_string(), n)),
+                "{}",
+                text
+            );
+            assert_eq!(version.build, build, "{}", text);
+            assert_eq!(version.to_string(), text.trim());
+        }
+    }
+
+    #[test]
+    fn rejects_non_numeric_versions() {
+        for text in ["", "beta", "v", "vX.1", "Unknown"] {
+            assert!(Version::parse(text).is_none(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn orders_versions() {
+        let ascending = [
+            ("1.2", "1.2.1"),
+            ("1.9", "1.10"),
+            ("1.2.3", "1.2.3.4"),
+            ("1.2a5", "1.2b1"),
+            ("1.2b3", "1.2b4"),
+            ("1.2-beta9", "1.2rc1"),
+            ("1.2rc1", "1.2"),
+            ("1.2.3", "1.2.3 (build 456)"),
+            ("1.2.3 (build 455)", "1.2.3 (build 456)"),
+            ("1.2.3 (build 999)", "1.2.4"),
+        ];
+        for (older, newer) in ascending {
+            assert!(v(older) < v(newer), "{} < {}", older, newer);
+        }
+    }
+
+    #[test]
+    fn trailing_zeros_and_prefixes_are_equal() {
+        let equal = [
+            ("1.2", "1.2.0"),
+            ("1.2", "1.2.0.0"),
+            ("v2.0", "2"),
+            ("1.2b3", "1.2-beta3"),
+            ("1.2a", "1.2 alpha 0"),
+        ];
+        for (a, b) in equal {
+            assert_eq!(v(a), v(b), "{} == {}", a, b);
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use super::x509::{signed_data_signer, Certificate};
+use crate::plugin::{CodeSignature, SignatureStatus};
+use crate::utils::error::Result;
+use anyhow::{anyhow, bail};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+const PE32_MAGIC: u16 = 0x10b;
+const PE32_PLUS_MAGIC: u16 = 0x20b;
+const SECURITY_DIRECTORY: usize = 4;
+const WIN_CERT_TYPE_PKCS_SIGNED_DATA: u16 = 0x0002;
+
+/// Reads the Authenticode signature of a DLL without asking Windows to verify it, so
+/// the chain isn't checked; this only reports who the file claims to be signed by.
+pub fn inspect_file(path: &Path) -> CodeSignature {
+    match File::open(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|mut file| inspect_pe(&mut file))
+    {
+        Ok(signature) => signature,
+        Err(e) => {
+            eprintln!("Error reading Authenticode signature of {:?}: {}", path, e);
+            CodeSignature::unsigned()
+        }
+    }
+}
+
+/// Finds the PKCS#7 blob in a PE image's security directory. Its file offset is a raw
+/// file position rather than an RVA, and the blob isn't mapped into memory. Only the
+/// headers and the certificate table are read, not the rest of the image.
+pub fn inspect_pe<R: Read + Seek>(reader: &mut R) -> Result<CodeSignature> {
+    let dos_header = read_up_to(reader, 0, 64)?;
+    if dos_header.get(0..2) != Some(b"MZ".as_slice()) {
+        bail!("Not a PE image");
+    }
+    let pe_offset = le32(&dos_header, 0x3c)? as u64;
+
+    // The 4-byte signature and 20-byte COFF header, then the optional header up to the
+    // end of the security directory entry.
+    let headers = read_up_to(reader, pe_offset, 24 + 112 + (SECURITY_DIRECTORY + 1) * 8)?;
+    if headers.get(0..4) != Some(b"PE\0\0".as_slice()) {
+        bail!("Missing PE signature");
+    }
+
+    let optional = 24;
+    let directories = match le16(&headers, optional)? {
+        PE32_MAGIC => optional + 96,
+        PE32_PLUS_MAGIC => optional + 112,
+        magic => bail!("Unknown PE optional header magic {:#06x}", magic),
+    };
+    let directory_count = le32(&headers, directories - 4)? as usize;
+    if directory_count <= SECURITY_DIRECTORY {
+        return Ok(CodeSignature::unsigned());
+    }
+
+    let entry = directories + SECURITY_DIRECTORY * 8;
+    let table_offset = le32(&headers, entry)? as u64;
+    let table_size = le32(&headers, entry + 4)? as usize;
+    if table_offset == 0 || table_size == 0 {
+        return Ok(CodeSignature::unsigned());
+    }
+    let table = read_at(reader, table_offset, table_size)?;
+
+    // WIN_CERTIFICATE entries are 8-byte aligned; the first PKCS#7 one is the primary
+    // signature and any others are nested or appended signatures.
+    let mut offset = 0;
+    while offset + 8 <= table.len() {
+        let length = le32(&table, offset)? as usize;
+        let certificate_type = le16(&table, offset + 6)?;
+        if length < 8 {
+            bail!("Malformed WIN_CERTIFICATE entry");
+        }
+        if certificate_type == WIN_CERT_TYPE_PKCS_SIGNED_DATA {
+            let blob = slice(&table, offset + 8, length - 8)?;
+            return Ok(signature_from(signed_data_signer(blob)?));
+        }
+        offset += (length + 7) & !7;
+    }
+
+    Ok(CodeSignature::unsigned())
+}
+
+fn signature_from(signer: Option<Certificate>) -> CodeSignature {
+    let Some(signer) = signer else {
+        return CodeSignature::unsigned();
+    };
+    CodeSignature {
+        status: SignatureStatus::Signed,
+        signer: signer.subject.common_name,
+        organization: signer.subject.organization,
+        team_id: None,
+        identifier: None,
+        valid_from: signer.not_before,
+        valid_until: signer.not_after,
+    }
+}
+
+/// Reads exactly `length` bytes starting at `offset`.
+fn read_at<R: Read + Seek>(reader: &mut R, offset: u64, length: usize) -> Result<Vec<u8>> {
+    let data = read_up_to(reader, offset, length)?;
+    if data.len() < length {
+        bail!("PE range {}+{} is out of bounds", offset, length);
+    }
+    Ok(data)
+}
+
+/// Reads up to `length` bytes starting at `offset`, fewer if the file ends first.
+fn read_up_to<R: Read + Seek>(reader: &mut R, offset: u64, length: usize) -> Result<Vec<u8>> {
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut data = Vec::new();
+    reader.by_ref().take(length as u64).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn slice(data: &[u8], offset: usize, length: usize) -> Result<&[u8]> {
+    data.get(offset..offset.saturating_add(length))
+        .ok_or_else(|| anyhow!("PE range {}+{} is out of bounds", offset, length))
+}
+
+fn le16(data: &[u8], offset: usize) -> Result<u16> {
+    Ok(u16::from_le_bytes(slice(data, offset, 2)?.try_into()?))
+}
+
+fn le32(data: &[u8], offset: usize) -> Result<u32> {
+    Ok(u32::from_le_bytes(slice(data, offset, 4)?.try_into()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::x509::tests::signed_data;
+    use super::*;
+    use std::io::Cursor;
+
+    const PE_OFFSET: usize = 64;
+    const TABLE_OFFSET: usize = 512;
+
+    /// A PE image whose security directory points at a `WIN_CERTIFICATE` holding
+    /// `cms`, or is empty when there's none.
+    fn pe(magic: u16, cms: Option<Vec<u8>>) -> Vec<u8> {
+        let mut image = vec![0; TABLE_OFFSET];
+        image[0..2].copy_from_slice(b"MZ");
+        image[0x3c..0x40].copy_from_slice(&(PE_OFFSET as u32).to_le_bytes());
+        image[PE_OFFSET..PE_OFFSET + 4].copy_from_slice(b"PE\0\0");
+
+        let optional = PE_OFFSET + 24;
+        image[optional..optional + 2].copy_from_slice(&magic.to_le_bytes());
+        let directories = optional + if magic == PE32_MAGIC { 96 } else { 112 };
+        image[directories - 4..directories].copy_from_slice(&16u32.to_le_bytes());
+
+        if let Some(cms) = cms {
+            let length = 8 + cms.len() as u32;
+            let entry = directories + SECURITY_DIRECTORY * 8;
+            image[entry..entry + 4].copy_from_slice(&(TABLE_OFFSET as u32).to_le_bytes());
+            image[entry + 4..entry + 8].copy_from_slice(&((length + 7) & !7).to_le_bytes());
+
+            image.extend(length.to_le_bytes());
+            image.extend(0x0200u16.to_le_bytes());
+            image.extend(WIN_CERT_TYPE_PKCS_SIGNED_DATA.to_le_bytes());
+            image.extend(cms);
+            image.resize((image.len() + 7) & !7, 0);
+        }
+        image
+    }
+
+    fn inspect(data: Vec<u8>) -> CodeSignature {
+        inspect_pe(&mut Cursor::new(data)).unwrap()
+    }
+
+    fn vendor_signature() -> Vec<u8> {
+        signed_data("Vendor Ltd", Some("Vendor Ltd"), None)
+    }
+
+    #[test]
+    fn signed_dll() {
+        for magic in [PE32_MAGIC, PE32_PLUS_MAGIC] {
+            let signature = inspect(pe(magic, Some(vendor_signature())));
+            assert_eq!(signature.status, SignatureStatus::Signed);
+            assert_eq!(signature.signer.as_deref(), Some("Vendor Ltd"));
+            assert_eq!(signature.publisher().as_deref(), Some("Vendor Ltd"));
+            assert_eq!(signature.team_id, None);
+            assert_eq!(signature.valid_from.as_deref(), Some("2024-01-01"));
+        }
+    }
+
+    #[test]
+    fn unsigned_dll() {
+        assert_eq!(
+            inspect(pe(PE32_PLUS_MAGIC, None)),
+            CodeSignature::unsigned()
+        );
+    }
+
+    #[test]
+    fn truncated_or_foreign_files_are_errors() {
+        let mut truncated = pe(PE32_PLUS_MAGIC, Some(vendor_signature()));
+        truncated.truncate(TABLE_OFFSET + 16);
+        assert!(inspect_pe(&mut Cursor::new(truncated)).is_err());
+
+        let mut unknown_magic = pe(PE32_PLUS_MAGIC, None);
+        unknown_magic[PE_OFFSET + 24] = 0x07;
+        assert!(inspect_pe(&mut Cursor::new(unknown_magic)).is_err());
+
+        assert!(inspect_pe(&mut Cursor::new(b"\x7fELF".to_vec())).is_err());
+    }
+}
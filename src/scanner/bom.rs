@@ -0,0 +1,316 @@
+use crate::utils::error::Result;
+use anyhow::{anyhow, bail};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BomEntryKind {
+    File,
+    Directory,
+    Symlink,
+    Device,
+}
+
+#[derive(Debug, Clone)]
+pub struct BomEntry {
+    /// Path relative to the package's install prefix, without the leading `./`.
+    pub path: PathBuf,
+    pub kind: BomEntryKind,
+    pub size: u64,
+}
+
+/// Reads the file list out of a bill-of-materials (`.bom`) file, as written by
+/// `mkbom` and left in `/var/db/receipts` by the macOS installer.
+///
+/// The format is a big-endian block store: a header pointing at a block index and a
+/// table of named variables, where the `Paths` variable is a B+ tree whose leaves
+/// hold (path info, file name) block pairs.
+pub fn parse_bom(data: &[u8]) -> Result<Vec<BomEntry>> {
+    let store = BomStore::new(data)?;
+
+    let tree = store.block(store.variable("Paths")?)?;
+    if tree.get(0..4) != Some(b"tree".as_slice()) {
+        bail!("BOM Paths variable does not point at a tree");
+    }
+
+    let mut nodes: HashMap<u32, (u32, String, BomEntryKind, u64)> = HashMap::new();
+    let mut visited = HashSet::new();
+    let mut block_id = be32(tree, 8)?;
+
+    // Walk down the left edge of the tree to the first leaf.
+    loop {
+        if !visited.insert(block_id) {
+            bail!("BOM path tree contains a cycle");
+        }
+        let node = store.block(block_id)?;
+        if be16(node, 0)? != 0 {
+            break;
+        }
+        block_id = be32(node, 12)?;
+    }
+
+    // Leaves are chained through their forward pointers.
+    visited.clear();
+    while block_id != 0 {
+        if !visited.insert(block_id) {
+            bail!("BOM path leaves contain a cycle");
+        }
+        let leaf = store.block(block_id)?;
+        let count = be16(leaf, 2)? as usize;
+
+        for i in 0..count {
+            let offset = 12 + i * 8;
+            let info = store.block(be32(leaf, offset)?)?;
+            let file = store.block(be32(leaf, offset + 4)?)?;
+
+            let id = be32(info, 0)?;
+            let details = store.block(be32(info, 4)?)?;
+            let kind = match details.first() {
+                Some(1) => BomEntryKind::File,
+                Some(2) => BomEntryKind::Directory,
+                Some(3) => BomEntryKind::Symlink,
+                _ => BomEntryKind::Device,
+            };
+            let size = be32(details, 18).unwrap_or(0) as u64;
+
+            let parent = be32(file, 0)?;
+            let name_bytes = file.get(4..).unwrap_or_default();
+            let name_end = name_bytes
+                .iter()
+                .position(|&b| b == 0)
+                .unwrap_or(name_bytes.len());
+            let name = String::from_utf8_lossy(&name_bytes[..name_end]).into_owned();
+
+            nodes.insert(id, (parent, name, kind, size));
+        }
+
+        block_id = be32(leaf, 4)?;
+    }
+
+    let mut entries = Vec::new();
+    for (id, (_, _, kind, size)) in &nodes {
+        let path = resolve_path(&nodes, *id)?;
+        if path.as_os_str().is_empty() {
+            continue;
+        }
+        entries.push(BomEntry {
+            path,
+            kind: *kind,
+            size: *size,
+        });
+    }
+
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+fn resolve_path(
+    nodes: &HashMap<u32, (u32, String, BomEntryKind, u64)>,
+    id: u32,
+) -> Result<PathBuf> {
+    let mut components = Vec::new();
+    let mut current = id;
+
+    while current != 0 {
+        if components.len() > 256 {
+            bail!("BOM path for entry {} is too deep", id);
+        }
+        let (parent, name, _, _) = nodes
+            .get(&current)
+            .ok_or_else(|| anyhow!("BOM entry {} has an unknown parent", id))?;
+        components.push(name.as_str());
+        current = *parent;
+    }
+
+    Ok(components
+        .iter()
+        .rev()
+        .flat_map(|name| name.split('/'))
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect())
+}
+
+struct BomStore<'a> {
+    data: &'a [u8],
+    index_offset: usize,
+    vars_offset: usize,
+}
+
+impl<'a> BomStore<'a> {
+    fn new(data: &'a [u8]) -> Result<Self> {
+        if data.get(0..8) != Some(b"BOMStore".as_slice()) {
+            bail!("Not a BOM file");
+        }
+        Ok(Self {
+            data,
+            index_offset: be32(data, 16)? as usize,
+            vars_offset: be32(data, 24)? as usize,
+        })
+    }
+
+    fn block(&self, id: u32) -> Result<&'a [u8]> {
+        let count = be32(self.data, self.index_offset)?;
+        if id >= count {
+            bail!("BOM block {} is out of range", id);
+        }
+        let entry = self.index_offset + 4 + id as usize * 8;
+        let address = be32(self.data, entry)? as usize;
+        let length = be32(self.data, entry + 4)? as usize;
+        self.data
+            .get(address..address + length)
+            .ok_or_else(|| anyhow!("BOM block {} extends past the end of the file", id))
+    }
+
+    fn variable(&self, wanted: &str) -> Result<u32> {
+        let count = be32(self.data, self.vars_offset)?;
+        let mut offset = self.vars_offset + 4;
+
+        for _ in 0..count {
+            let block = be32(self.data, offset)?;
+            let length = *self
+                .data
+                .get(offset + 4)
+                .ok_or_else(|| anyhow!("Truncated BOM variable table"))?
+                as usize;
+            let name = self
+                .data
+                .get(offset + 5..offset + 5 + length)
+                .ok_or_else(|| anyhow!("Truncated BOM variable table"))?;
+            if name == wanted.as_bytes() {
+                return Ok(block);
+            }
+            offset += 5 + length;
+        }
+
+        bail!("BOM has no {} variable", wanted)
+    }
+}
+
+fn be32(data: &[u8], offset: usize) -> Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| anyhow!("Unexpected end of BOM data at offset {}", offset))
+}
+
+fn be16(data: &[u8], offset: usize) -> Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .ok_or_else(|| anyhow!("Unexpected end of BOM data at offset {}", offset))
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn push(blocks: &mut Vec<Vec<u8>>, block: Vec<u8>) -> u32 {
+        blocks.push(block);
+        (blocks.len() - 1) as u32
+    }
+
+    /// Builds a BOM listing `entries` as (path, kind, size) in a single leaf, the way
+    /// `mkbom` lays out a small package. A folder must be listed before its contents.
+    pub(in crate::scanner) fn build_bom(entries: &[(&str, BomEntryKind, u32)]) -> Vec<u8> {
+        let mut blocks = vec![Vec::new()];
+        let mut ids: HashMap<&Path, u32> = HashMap::new();
+        let mut pairs = Vec::new();
+
+        for (i, (path, kind, size)) in entries.iter().enumerate() {
+            let id = i as u32 + 1;
+            let path = Path::new(path);
+            ids.insert(path, id);
+
+            let mut details = vec![0; 22];
+            details[0] = match kind {
+                BomEntryKind::File => 1,
+                BomEntryKind::Directory => 2,
+                BomEntryKind::Symlink => 3,
+                BomEntryKind::Device => 4,
+            };
+            details[18..22].copy_from_slice(&size.to_be_bytes());
+            let details = push(&mut blocks, details);
+
+            let info = [id.to_be_bytes(), details.to_be_bytes()].concat();
+            let info = push(&mut blocks, info);
+
+            let parent = path.parent().and_then(|p| ids.get(p)).copied().unwrap_or(0);
+            let name = path.file_name().map_or(".", |n| n.to_str().unwrap());
+            let file = [&parent.to_be_bytes(), name.as_bytes(), &[0]].concat();
+            let file = push(&mut blocks, file);
+            pairs.push((info, file));
+        }
+
+        let mut leaf = [1u16.to_be_bytes(), (pairs.len() as u16).to_be_bytes()].concat();
+        leaf.extend([0; 8]);
+        for (info, file) in pairs {
+            leaf.extend(info.to_be_bytes());
+            leaf.extend(file.to_be_bytes());
+        }
+        let leaf = push(&mut blocks, leaf);
+        let tree = [b"tree".as_slice(), &1u32.to_be_bytes(), &leaf.to_be_bytes()].concat();
+        let tree = push(&mut blocks, tree);
+
+        let mut data = vec![0; 32];
+        let mut index = (blocks.len() as u32).to_be_bytes().to_vec();
+        for block in &blocks {
+            index.extend((data.len() as u32).to_be_bytes());
+            index.extend((block.len() as u32).to_be_bytes());
+            data.extend(block);
+        }
+        let index_offset = data.len() as u32;
+        data.extend(&index);
+        let vars_offset = data.len() as u32;
+        let mut vars = 1u32.to_be_bytes().to_vec();
+        vars.extend(tree.to_be_bytes());
+        vars.push(5);
+        vars.extend(b"Paths");
+        data.extend(&vars);
+
+        data[0..8].copy_from_slice(b"BOMStore");
+        data[8..12].copy_from_slice(&1u32.to_be_bytes());
+        data[12..16].copy_from_slice(&(blocks.len() as u32).to_be_bytes());
+        data[16..20].copy_from_slice(&index_offset.to_be_bytes());
+        data[20..24].copy_from_slice(&(index.len() as u32).to_be_bytes());
+        data[24..28].copy_from_slice(&vars_offset.to_be_bytes());
+        data[28..32].copy_from_slice(&(vars.len() as u32).to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn lists_paths_relative_to_the_prefix() {
+        let bom = build_bom(&[
+            (".", BomEntryKind::Directory, 0),
+            ("./Synth.vst3", BomEntryKind::Directory, 0),
+            ("./Synth.vst3/Info.plist", BomEntryKind::File, 120),
+            ("./Synth.vst3/Synth", BomEntryKind::File, 4096),
+            ("./Synth.vst3/Current", BomEntryKind::Symlink, 0),
+        ]);
+
+        let entries: Vec<_> = parse_bom(&bom)
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.path, e.kind, e.size))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("Synth.vst3".into(), BomEntryKind::Directory, 0),
+                ("Synth.vst3/Current".into(), BomEntryKind::Symlink, 0),
+                ("Synth.vst3/Info.plist".into(), BomEntryKind::File, 120),
+                ("Synth.vst3/Synth".into(), BomEntryKind::File, 4096),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_boms_are_errors() {
+        let bom = build_bom(&[("./Synth.vst3", BomEntryKind::File, 1)]);
+        assert!(parse_bom(b"Not a BOM at all").is_err());
+        assert!(parse_bom(&bom[..bom.len() / 2]).is_err());
+
+        let mut no_paths = bom.clone();
+        let name = no_paths.len() - 5;
+        no_paths[name..].copy_from_slice(b"Other");
+        assert!(parse_bom(&no_paths).is_err());
+    }
+}
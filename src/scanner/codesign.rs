@@ -0,0 +1,360 @@
+use super::x509::signed_data_signer;
+use crate::plugin::{CodeSignature, SignatureStatus};
+use crate::utils::error::Result;
+use anyhow::{anyhow, bail};
+use plist::Value;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+const FAT_MAGIC: u32 = 0xcafe_babe;
+const FAT_MAGIC_64: u32 = 0xcafe_babf;
+const MH_MAGIC: u32 = 0xfeed_face;
+const MH_MAGIC_64: u32 = 0xfeed_facf;
+const LC_CODE_SIGNATURE: u32 = 0x1d;
+
+const CSMAGIC_EMBEDDED_SIGNATURE: u32 = 0xfade_0cc0;
+const CSMAGIC_CODEDIRECTORY: u32 = 0xfade_0c02;
+const CSMAGIC_BLOBWRAPPER: u32 = 0xfade_0b01;
+const CSSLOT_CODEDIRECTORY: u32 = 0;
+const CSSLOT_SIGNATURESLOT: u32 = 0x10000;
+const CS_ADHOC: u32 = 0x2;
+const CS_LINKER_SIGNED: u32 = 0x2_0000;
+const CS_SUPPORTSTEAMID: u32 = 0x2_0200;
+
+/// Inspects the main executable of a bundle. A signed bundle that carries a stapled
+/// notarization ticket (`Contents/CodeResources`) is reported as notarized; tickets
+/// that only live on Apple's servers can't be seen offline.
+pub fn inspect_bundle(bundle: &Path) -> CodeSignature {
+    let Some(executable) = bundle_executable(bundle) else {
+        return CodeSignature::unsigned();
+    };
+
+    let mut signature = match File::open(&executable)
+        .map_err(anyhow::Error::from)
+        .and_then(|mut file| inspect_macho(&mut file))
+    {
+        Ok(signature) => signature,
+        Err(e) => {
+            eprintln!("Error reading code signature of {:?}: {}", executable, e);
+            return CodeSignature::unsigned();
+        }
+    };
+
+    if signature.status == SignatureStatus::Signed
+        && bundle.join("Contents/CodeResources").is_file()
+    {
+        signature.status = SignatureStatus::Notarized;
+    }
+    signature
+}
+
+fn bundle_executable(bundle: &Path) -> Option<PathBuf> {
+    let macos_dir = bundle.join("Contents/MacOS");
+
+    let declared = plist::from_file::<_, Value>(bundle.join("Contents/Info.plist"))
+        .ok()
+        .and_then(|plist| {
+            plist
+                .as_dictionary()?
+                .get("CFBundleExecutable")?
+                .as_string()
+                .map(|name| macos_dir.join(name))
+        })
+        .filter(|path| path.is_file());
+
+    declared.or_else(|| {
+        std::fs::read_dir(&macos_dir)
+            .ok()?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .find(|p| p.is_file())
+    })
+}
+
+/// Reads the embedded signature of a thin or universal Mach-O image. Every slice of a
+/// universal binary is signed by the same identity, so only the first is read. Only
+/// the headers, load commands and signature are read, not the rest of the binary.
+pub fn inspect_macho<R: Read + Seek>(reader: &mut R) -> Result<CodeSignature> {
+    let fat_header = read_up_to(reader, 0, 8 + 32)?;
+    let base = match be32(&fat_header, 0)? {
+        FAT_MAGIC => be32(&fat_header, 8 + 8)? as u64,
+        FAT_MAGIC_64 => be64(&fat_header, 8 + 8)?,
+        _ => 0,
+    };
+
+    let header = read_up_to(reader, base, 32)?;
+    let header_size = match le32(&header, 0)? {
+        MH_MAGIC => 28,
+        MH_MAGIC_64 => 32,
+        _ => bail!("Not a Mach-O image"),
+    };
+    let command_count = le32(&header, 16)?;
+    let commands_size = le32(&header, 20)? as usize;
+    let commands = read_at(reader, base.saturating_add(header_size), commands_size)?;
+
+    let mut offset = 0;
+    for _ in 0..command_count {
+        let command = le32(&commands, offset)?;
+        let command_size = le32(&commands, offset + 4)? as usize;
+        if command_size < 8 {
+            bail!("Malformed Mach-O load command");
+        }
+        if command == LC_CODE_SIGNATURE {
+            let data_offset = le32(&commands, offset + 8)? as u64;
+            let data_size = le32(&commands, offset + 12)? as usize;
+            let blob = read_at(reader, base.saturating_add(data_offset), data_size)?;
+            return parse_superblob(&blob);
+        }
+        offset += command_size;
+    }
+
+    Ok(CodeSignature::unsigned())
+}
+
+fn parse_superblob(blob: &[u8]) -> Result<CodeSignature> {
+    if be32(blob, 0)? != CSMAGIC_EMBEDDED_SIGNATURE {
+        bail!("Code signature is not an embedded signature blob");
+    }
+
+    let mut flags = 0;
+    let mut identifier = None;
+    let mut team_id = None;
+    let mut signer = None;
+
+    for i in 0..be32(blob, 8)? as usize {
+        let slot = be32(blob, 12 + i * 8)?;
+        let offset = be32(blob, 16 + i * 8)? as usize;
+        let inner = blob
+            .get(offset..)
+            .ok_or_else(|| anyhow!("Code signature slot out of range"))?;
+
+        match (slot, be32(inner, 0)?) {
+            (CSSLOT_CODEDIRECTORY, CSMAGIC_CODEDIRECTORY) => {
+                let version = be32(inner, 8)?;
+                flags = be32(inner, 12)?;
+                identifier = c_string(inner, be32(inner, 20)? as usize);
+                if version >= CS_SUPPORTSTEAMID {
+                    let team_offset = be32(inner, 48)? as usize;
+                    if team_offset != 0 {
+                        team_id = c_string(inner, team_offset);
+                    }
+                }
+            }
+            (CSSLOT_SIGNATURESLOT, CSMAGIC_BLOBWRAPPER) => {
+                let length = be32(inner, 4)? as usize;
+                if length > 8 {
+                    let cms = slice(inner, 8, length - 8)?;
+                    signer = signed_data_signer(cms)?;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let signer = signer.as_ref();
+    let status = if flags & (CS_ADHOC | CS_LINKER_SIGNED) != 0 || signer.is_none() {
+        SignatureStatus::AdHoc
+    } else {
+        SignatureStatus::Signed
+    };
+
+    Ok(CodeSignature {
+        status,
+        signer: signer.and_then(|c| c.subject.common_name.clone()),
+        organization: signer.and_then(|c| c.subject.organization.clone()),
+        team_id: team_id.or_else(|| signer.and_then(|c| c.subject.organizational_unit.clone())),
+        identifier,
+        valid_from: signer.and_then(|c| c.not_before.clone()),
+        valid_until: signer.and_then(|c| c.not_after.clone()),
+    })
+}
+
+fn c_string(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let end = bytes.iter().position(|&b| b == 0)?;
+    let value = String::from_utf8_lossy(&bytes[..end]).into_owned();
+    (!value.is_empty()).then_some(value)
+}
+
+/// Reads exactly `length` bytes starting at `offset`.
+fn read_at<R: Read + Seek>(reader: &mut R, offset: u64, length: usize) -> Result<Vec<u8>> {
+    let data = read_up_to(reader, offset, length)?;
+    if data.len() < length {
+        bail!("Mach-O range {}+{} is out of bounds", offset, length);
+    }
+    Ok(data)
+}
+
+/// Reads up to `length` bytes starting at `offset`, fewer if the file ends first.
+fn read_up_to<R: Read + Seek>(reader: &mut R, offset: u64, length: usize) -> Result<Vec<u8>> {
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut data = Vec::new();
+    reader.by_ref().take(length as u64).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn slice(data: &[u8], offset: usize, length: usize) -> Result<&[u8]> {
+    data.get(offset..offset.saturating_add(length))
+        .ok_or_else(|| anyhow!("Mach-O range {}+{} is out of bounds", offset, length))
+}
+
+fn be32(data: &[u8], offset: usize) -> Result<u32> {
+    Ok(u32::from_be_bytes(slice(data, offset, 4)?.try_into()?))
+}
+
+fn be64(data: &[u8], offset: usize) -> Result<u64> {
+    Ok(u64::from_be_bytes(slice(data, offset, 8)?.try_into()?))
+}
+
+fn le32(data: &[u8], offset: usize) -> Result<u32> {
+    Ok(u32::from_le_bytes(slice(data, offset, 4)?.try_into()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::x509::tests::signed_data;
+    use super::*;
+    use std::io::Cursor;
+
+    fn code_directory(flags: u32, identifier: &str, team: &str) -> Vec<u8> {
+        let mut cd = vec![0; 52];
+        cd[0..4].copy_from_slice(&CSMAGIC_CODEDIRECTORY.to_be_bytes());
+        cd[8..12].copy_from_slice(&CS_SUPPORTSTEAMID.to_be_bytes());
+        cd[12..16].copy_from_slice(&flags.to_be_bytes());
+        cd[20..24].copy_from_slice(&52u32.to_be_bytes());
+        let team_offset = 52 + identifier.len() as u32 + 1;
+        cd[48..52].copy_from_slice(&team_offset.to_be_bytes());
+        cd.extend(identifier.as_bytes());
+        cd.push(0);
+        cd.extend(team.as_bytes());
+        cd.push(0);
+        let length = cd.len() as u32;
+        cd[4..8].copy_from_slice(&length.to_be_bytes());
+        cd
+    }
+
+    fn superblob(code_directory: Vec<u8>, cms: Option<Vec<u8>>) -> Vec<u8> {
+        let mut blobs = vec![(CSSLOT_CODEDIRECTORY, code_directory)];
+        if let Some(cms) = cms {
+            let mut wrapper = CSMAGIC_BLOBWRAPPER.to_be_bytes().to_vec();
+            wrapper.extend((cms.len() as u32 + 8).to_be_bytes());
+            wrapper.extend(cms);
+            blobs.push((CSSLOT_SIGNATURESLOT, wrapper));
+        }
+
+        let mut index = Vec::new();
+        let mut data = Vec::new();
+        let header_size = 12 + blobs.len() * 8;
+        for (slot, blob) in blobs {
+            index.extend(slot.to_be_bytes());
+            index.extend(((header_size + data.len()) as u32).to_be_bytes());
+            data.extend(blob);
+        }
+
+        let mut superblob = CSMAGIC_EMBEDDED_SIGNATURE.to_be_bytes().to_vec();
+        superblob.extend(((header_size + data.len()) as u32).to_be_bytes());
+        superblob.extend((index.len() as u32 / 8).to_be_bytes());
+        superblob.extend(index);
+        superblob.extend(data);
+        superblob
+    }
+
+    /// A 64-bit Mach-O image with one load command: the code signature when there is
+    /// one, or an `LC_UUID` otherwise.
+    fn macho(signature: Option<Vec<u8>>) -> Vec<u8> {
+        const SIGNATURE_OFFSET: u32 = 64;
+
+        let mut image = vec![0; 32];
+        image[0..4].copy_from_slice(&MH_MAGIC_64.to_le_bytes());
+        image[16..20].copy_from_slice(&1u32.to_le_bytes());
+        image[20..24].copy_from_slice(&16u32.to_le_bytes());
+
+        let (command, size) = match &signature {
+            Some(blob) => (LC_CODE_SIGNATURE, blob.len() as u32),
+            None => (0x1b, 0),
+        };
+        for field in [command, 16, SIGNATURE_OFFSET, size] {
+            image.extend(field.to_le_bytes());
+        }
+        image.resize(SIGNATURE_OFFSET as usize, 0);
+        image.extend(signature.unwrap_or_default());
+        image
+    }
+
+    fn universal(image: Vec<u8>) -> Vec<u8> {
+        const SLICE_OFFSET: u32 = 4096;
+
+        let mut fat = Vec::new();
+        for field in [
+            FAT_MAGIC,
+            1,
+            0x0100_000c,
+            0,
+            SLICE_OFFSET,
+            image.len() as u32,
+            12,
+        ] {
+            fat.extend(field.to_be_bytes());
+        }
+        fat.resize(SLICE_OFFSET as usize, 0);
+        fat.extend(image);
+        fat
+    }
+
+    fn inspect(data: Vec<u8>) -> CodeSignature {
+        inspect_macho(&mut Cursor::new(data)).unwrap()
+    }
+
+    fn developer_id() -> Vec<u8> {
+        let cms = signed_data(
+            "Developer ID Application: Vendor Ltd (ABCDE12345)",
+            Some("Vendor Ltd"),
+            Some("ABCDE12345"),
+        );
+        superblob(
+            code_directory(0, "com.vendor.synth", "ABCDE12345"),
+            Some(cms),
+        )
+    }
+
+    #[test]
+    fn developer_id_signature() {
+        let signature = inspect(macho(Some(developer_id())));
+        assert_eq!(signature.status, SignatureStatus::Signed);
+        assert_eq!(signature.team_id.as_deref(), Some("ABCDE12345"));
+        assert_eq!(signature.identifier.as_deref(), Some("com.vendor.synth"));
+        assert_eq!(signature.publisher().as_deref(), Some("Vendor Ltd"));
+        assert_eq!(signature.valid_until.as_deref(), Some("2029-06-30"));
+    }
+
+    #[test]
+    fn universal_binaries_read_the_first_slice() {
+        let signature = inspect(universal(macho(Some(developer_id()))));
+        assert_eq!(signature.status, SignatureStatus::Signed);
+        assert_eq!(signature.team_id.as_deref(), Some("ABCDE12345"));
+    }
+
+    #[test]
+    fn ad_hoc_signature() {
+        let blob = superblob(code_directory(CS_ADHOC, "synth", ""), None);
+        let signature = inspect(macho(Some(blob)));
+        assert_eq!(signature.status, SignatureStatus::AdHoc);
+        assert_eq!(signature.identifier.as_deref(), Some("synth"));
+        assert_eq!(signature.team_id, None);
+        assert_eq!(signature.publisher(), None);
+    }
+
+    #[test]
+    fn unsigned_image() {
+        assert_eq!(inspect(macho(None)), CodeSignature::unsigned());
+    }
+
+    #[test]
+    fn truncated_or_foreign_files_are_errors() {
+        let mut truncated = macho(Some(developer_id()));
+        truncated.truncate(100);
+        assert!(inspect_macho(&mut Cursor::new(truncated)).is_err());
+        assert!(inspect_macho(&mut Cursor::new(b"#!/bin/sh\n".to_vec())).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::plugin::Plugin;
 use crate::utils::error::Result;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 use super::metadata::{parse_aax_plugin, parse_vst2_plugin, parse_vst3_plugin};
@@ -10,6 +10,33 @@ use super::macos::scan_au_directory;
 
 pub struct PluginScanner;
 
+/// Where disabled plugins from `dir` are kept: a sibling folder such as
+/// `VST3 (Disabled)`, which no DAW searches.
+pub fn disabled_directory(dir: &Path) -> PathBuf {
+    let name = dir.file_name().unwrap_or_default().to_string_lossy();
+    dir.with_file_name(format!("{} (Disabled)", name))
+}
+
+/// Scans a format folder and its disabled sibling, marking what's found in the latter.
+fn scan_with_disabled(
+    dir: &Path,
+    scan: impl Fn(&Path) -> Result<Vec<Plugin>>,
+) -> Result<Vec<Plugin>> {
+    let mut plugins = Vec::new();
+    if dir.exists() {
+        plugins.extend(scan(dir)?);
+    }
+
+    let disabled = disabled_directory(dir);
+    if disabled.exists() {
+        plugins.extend(scan(&disabled)?.into_iter().map(|mut plugin| {
+            plugin.disabled = true;
+            plugin
+        }));
+    }
+    Ok(plugins)
+}
+
 impl PluginScanner {
     pub fn new() -> Self {
         Self {}
@@ -19,35 +46,39 @@ impl PluginScanner {
         let mut plugins = Vec::new();
 
         for path in self.get_vst2_paths() {
-            if path.exists() {
-                plugins.extend(self.scan_vst2_directory(&path)?);
-            }
+            plugins.extend(scan_with_disabled(&path, |dir| self.scan_vst2_directory(dir))?);
         }
 
         for path in self.get_vst3_paths() {
-            if path.exists() {
-                plugins.extend(self.scan_vst3_directory(&path)?);
-            }
+            plugins.extend(scan_with_disabled(&path, |dir| self.scan_vst3_directory(dir))?);
         }
 
         for path in self.get_aax_paths() {
-            if path.exists() {
-                plugins.extend(self.scan_aax_directory(&path)?);
-            }
+            plugins.extend(scan_with_disabled(&path, |dir| self.scan_aax_directory(dir))?);
         }
 
         #[cfg(target_os = "macos")]
         {
             for path in super::macos::get_au_paths() {
-                if path.exists() {
-                    plugins.extend(scan_au_directory(&path)?);
-                }
+                plugins.extend(scan_with_disabled(&path, scan_au_directory)?);
             }
         }
 
         Ok(plugins)
     }
 
+    /// Every folder the scanner searches, whether or not it exists.
+    pub fn plugin_directories(&self) -> Vec<std::path::PathBuf> {
+        let mut dirs = self.get_vst2_paths();
+        dirs.extend(self.get_vst3_paths());
+        dirs.extend(self.get_aax_paths());
+
+        #[cfg(target_os = "macos")]
+        dirs.extend(super::macos::get_au_paths());
+
+        dirs
+    }
+
     #[cfg(target_os = "windows")]
     fn get_vst2_paths(&self) -> Vec<std::path::PathBuf> {
         super::windows::get_vst2_paths()
@@ -0,0 +1,134 @@
+use crate::utils::config::config_dir;
+use crate::utils::error::Result;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackedUpFile {
+    pub original: PathBuf,
+    pub copy: PathBuf,
+}
+
+/// Copies of every cache file touched by one cleanup, kept under
+/// `<config dir>/cache-backups/<timestamp>` until the cleanup is undone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheBackup {
+    pub dir: PathBuf,
+    pub files: Vec<BackedUpFile>,
+}
+
+impl CacheBackup {
+    pub fn create() -> Result<Self> {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        let root = backups_root().context("No configuration directory on this system")?;
+        Ok(Self::new_in(root.join(stamp.to_string())))
+    }
+
+    pub fn new_in(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            files: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Copies `path` into the backup before it is edited or deleted. A file is only
+    /// copied the first time, so the backup always holds the original.
+    pub fn save_copy(&mut self, path: &Path) -> Result<()> {
+        if self.files.iter().any(|f| f.original == path) {
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(&self.dir)?;
+        let copy = self.dir.join(self.files.len().to_string());
+        std::fs::copy(path, &copy).with_context(|| format!("Failed to back up {:?}", path))?;
+        self.files.push(BackedUpFile {
+            original: path.to_path_buf(),
+            copy,
+        });
+        Ok(())
+    }
+
+    /// Puts every file back as it was, then discards the backup.
+    pub fn restore(&self) -> Result<()> {
+        for file in &self.files {
+            if let Some(parent) = file.original.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(&file.copy, &file.original)
+                .with_context(|| format!("Failed to restore {:?}", file.original))?;
+        }
+        std::fs::remove_dir_all(&self.dir)
+            .with_context(|| format!("Failed to remove backup {:?}", self.dir))
+    }
+}
+
+fn backups_root() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("cache-backups"))
+}
+
+/// Makes a DAW forget plugins that were moved to the bin, backing up whatever it
+/// changes first.
+pub trait DawCacheCleaner {
+    fn daw(&self) -> &'static str;
+    /// Returns how many cache entries or files were removed.
+    fn forget(&self, removed: &[PathBuf], backup: &mut CacheBackup) -> Result<usize>;
+}
+
+/// The AU cache macOS shares between hosts. It can't be edited entry by entry, so it
+/// is deleted whenever a component goes and rebuilt on the next host launch.
+#[cfg(target_os = "macos")]
+pub struct AuCache {
+    pub dir: Option<PathBuf>,
+}
+
+#[cfg(target_os = "macos")]
+impl AuCache {
+    pub fn system() -> Self {
+        Self {
+            dir: dirs::cache_dir().map(|dir| dir.join("AudioUnitCache")),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl DawCacheCleaner for AuCache {
+    fn daw(&self) -> &'static str {
+        "Audio Units"
+    }
+
+    fn forget(&self, removed: &[PathBuf], backup: &mut CacheBackup) -> Result<usize> {
+        let removed_component = removed.iter().any(|p| {
+            p.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("component"))
+        });
+        if !removed_component {
+            return Ok(0);
+        }
+        let Some(entries) = self
+            .dir
+            .as_ref()
+            .and_then(|dir| std::fs::read_dir(dir).ok())
+        else {
+            return Ok(0);
+        };
+
+        let mut count = 0;
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if path.is_file() {
+                backup.save_copy(&path)?;
+                std::fs::remove_file(&path)?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
@@ -0,0 +1,155 @@
+use super::{DawCacheEntry, DawCacheReader, DawStatus};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::PathBuf;
+
+static PATH_VALUE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"name="Path"\s+value="([^"]*)""#).unwrap());
+
+/// Cubase's plugin blocklists (`Vst2xPlugin Blocklist Cubase.xml` and its VST3
+/// counterpart) in each version's preferences folder. Entries are
+/// `<string name="Path" value="C:\...\Foo.dll"/>` items.
+pub struct CubaseBlocklist {
+    pub preference_dirs: Vec<PathBuf>,
+}
+
+impl CubaseBlocklist {
+    pub fn system() -> Self {
+        let mut preference_dirs = Vec::new();
+        if let Some(root) = steinberg_root() {
+            if let Ok(entries) = std::fs::read_dir(root) {
+                preference_dirs = entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .filter(|p| {
+                        p.is_dir()
+                            && p.file_name()
+                                .and_then(|n| n.to_str())
+                                .is_some_and(|n| n.starts_with("Cubase"))
+                    })
+                    .collect();
+            }
+        }
+        Self { preference_dirs }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn steinberg_root() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("Steinberg"))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn steinberg_root() -> Option<PathBuf> {
+    dirs::preference_dir()
+}
+
+impl DawCacheReader for CubaseBlocklist {
+    fn daw(&self) -> &'static str {
+        "Cubase"
+    }
+
+    fn cache_files(&self) -> Vec<PathBuf> {
+        self.preference_dirs
+            .iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flatten()
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| {
+                p.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
+                    let n = n.to_lowercase();
+                    n.ends_with(".xml") && (n.contains("blocklist") || n.contains("blacklist"))
+                })
+            })
+            .collect()
+    }
+
+    fn parse(&self, content: &str) -> Vec<DawCacheEntry> {
+        PATH_VALUE
+            .captures_iter(content)
+            .filter_map(|caps| {
+                let path = unescape_xml(&caps[1]);
+                let file_name = path.rsplit(['/', '\\']).next()?.to_string();
+                (!file_name.is_empty()).then(|| DawCacheEntry {
+                    daw: self.daw(),
+                    file_name,
+                    path: Some(PathBuf::from(&path)),
+                    status: DawStatus::Blocklisted,
+                })
+            })
+            .collect()
+    }
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<settings>
+   <member name="Plugins">
+      <list name="Items" type="obj">
+         <obj class="PluginInfo">
+            <string name="Path" value="C:\Program Files\Common Files\VST3\Crashy.vst3"/>
+         </obj>
+         <obj class="PluginInfo">
+            <string name="Path" value="C:\VST\Tom &amp; Jerry.dll"/>
+         </obj>
+         <obj class="PluginInfo">
+            <string name="Path" value=""/>
+         </obj>
+      </list>
+   </member>
+</settings>
+"#;
+
+    #[test]
+    fn blocklisted_paths() {
+        let reader = CubaseBlocklist {
+            preference_dirs: Vec::new(),
+        };
+        let entries: Vec<_> = reader
+            .parse(FIXTURE)
+            .into_iter()
+            .map(|e| (e.file_name, e.status))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("Crashy.vst3".to_string(), DawStatus::Blocklisted),
+                ("Tom & Jerry.dll".to_string(), DawStatus::Blocklisted),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_blocklists_from_each_preference_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let cubase = dir.path().join("Cubase 13_64");
+        std::fs::create_dir(&cubase).unwrap();
+        std::fs::write(cubase.join("Vst2xPlugin Blocklist Cubase.xml"), FIXTURE).unwrap();
+        std::fs::write(cubase.join("Defaults.xml"), FIXTURE).unwrap();
+
+        let reader = CubaseBlocklist {
+            preference_dirs: vec![cubase],
+        };
+        let entries = reader.read();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].path.as_deref(),
+            Some(std::path::Path::new(
+                r"C:\Program Files\Common Files\VST3\Crashy.vst3"
+            ))
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Readers for the scan results DAWs keep about plugins they failed to load or were
+//! told to ignore, and cleaners that make DAWs forget plugins once they are removed.
+//! Each DAW gets a [`DawCacheReader`]; Reaper's plugin ini and Cubase's blocklist are
+//! covered so far. Ableton (SQLite), Logic (binary AU cache) and Bitwig (binary index)
+//! need their own readers before they can be added here.
+
+mod cleanup;
+mod cubase;
+mod reaper;
+
+use crate::plugin::Plugin;
+use std::path::PathBuf;
+
+pub use cleanup::{CacheBackup, DawCacheCleaner};
+pub use cubase::CubaseBlocklist;
+pub use reaper::ReaperCache;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DawStatus {
+    FailedValidation,
+    Blocklisted,
+}
+
+impl DawStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DawStatus::FailedValidation => "failed validation",
+            DawStatus::Blocklisted => "blocklisted",
+        }
+    }
+}
+
+/// One plugin a DAW has flagged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DawCacheEntry {
+    pub daw: &'static str,
+    /// File or bundle name as the DAW recorded it.
+    pub file_name: String,
+    /// Full path, for caches that record one.
+    pub path: Option<PathBuf>,
+    pub status: DawStatus,
+}
+
+impl DawCacheEntry {
+    /// Caches that only keep a file name are matched on it, the way the DAW itself
+    /// does; Reaper also swaps spaces for underscores, so those are treated alike.
+    pub fn matches(&self, plugin: &Plugin) -> bool {
+        if let Some(path) = &self.path {
+            if path.starts_with(&plugin.path) {
+                return true;
+            }
+        }
+        plugin
+            .path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| cache_key(name) == cache_key(&self.file_name))
+    }
+}
+
+fn cache_key(file_name: &str) -> String {
+    file_name.to_lowercase().replace(' ', "_")
+}
+
+/// A DAW's plugin cache. `parse` only sees file contents so readers can be checked
+/// against fixture files; `cache_files` says where the live ones are.
+pub trait DawCacheReader {
+    fn daw(&self) -> &'static str;
+    fn cache_files(&self) -> Vec<PathBuf>;
+    fn parse(&self, content: &str) -> Vec<DawCacheEntry>;
+
+    fn read(&self) -> Vec<DawCacheEntry> {
+        self.cache_files()
+            .iter()
+            .filter(|file| file.is_file())
+            .flat_map(|file| match std::fs::read_to_string(file) {
+                Ok(content) => self.parse(&content),
+                Err(e) => {
+                    eprintln!("Error reading {} cache {:?}: {}", self.daw(), file, e);
+                    Vec::new()
+                }
+            })
+            .collect()
+    }
+}
+
+pub fn system_readers() -> Vec<Box<dyn DawCacheReader>> {
+    vec![
+        Box::new(ReaperCache::system()),
+        Box::new(CubaseBlocklist::system()),
+    ]
+}
+
+pub fn system_cleaners() -> Vec<Box<dyn DawCacheCleaner>> {
+    vec![
+        Box::new(ReaperCache::system()),
+        #[cfg(target_os = "macos")]
+        Box::new(cleanup::AuCache::system()),
+    ]
+}
+
+/// Runs every cleaner over the removed plugins, returning one message per DAW whose
+/// cache couldn't be cleaned. What did change is recorded in `backup`.
+pub fn clear_daw_caches(
+    cleaners: &[Box<dyn DawCacheCleaner>],
+    removed: &[PathBuf],
+    backup: &mut CacheBackup,
+) -> Vec<String> {
+    cleaners
+        .iter()
+        .filter_map(|cleaner| {
+            cleaner
+                .forget(removed, backup)
+                .err()
+                .map(|e| format!("{}: {}", cleaner.daw(), e))
+        })
+        .collect()
+}
+
+/// Everything the given DAWs have flagged, in reader order.
+pub fn read_daw_caches(readers: &[Box<dyn DawCacheReader>]) -> Vec<DawCacheEntry> {
+    readers.iter().flat_map(|reader| reader.read()).collect()
+}
@@ -0,0 +1,196 @@
+use super::cleanup::{CacheBackup, DawCacheCleaner};
+use super::{cache_key, DawCacheEntry, DawCacheReader, DawStatus};
+use crate::utils::error::Result;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+const CACHE_FILES: [&str; 3] = [
+    "reaper-vstplugins64.ini",
+    "reaper-vstplugins_arm64.ini",
+    "reaper-vstplugins.ini",
+];
+
+/// Reaper's VST cache. Each `[vstcache]` line reads
+/// `File_Name.dll=<timestamp>,<id>,<display name>`; a plugin that failed to load keeps
+/// only the timestamp, so Reaper skips it until it is rescanned.
+pub struct ReaperCache {
+    pub files: Vec<PathBuf>,
+}
+
+impl ReaperCache {
+    pub fn system() -> Self {
+        let files = dirs::config_dir()
+            .map(|dir| {
+                CACHE_FILES
+                    .iter()
+                    .map(|name| dir.join("REAPER").join(name))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { files }
+    }
+}
+
+impl DawCacheReader for ReaperCache {
+    fn daw(&self) -> &'static str {
+        "Reaper"
+    }
+
+    fn cache_files(&self) -> Vec<PathBuf> {
+        self.files.clone()
+    }
+
+    fn parse(&self, content: &str) -> Vec<DawCacheEntry> {
+        let mut entries = Vec::new();
+        let mut in_cache = false;
+
+        for line in content.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_cache = line.eq_ignore_ascii_case("[vstcache]");
+                continue;
+            }
+            if !in_cache {
+                continue;
+            }
+            let Some((file_name, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            let mut fields = value.splitn(3, ',');
+            let _timestamp = fields.next();
+            let loaded = fields.next().is_some_and(|id| !id.trim().is_empty());
+            // Lines such as `<SHELL>` describe shell plugins rather than files.
+            if !file_name.is_empty() && !file_name.starts_with('<') && !loaded {
+                entries.push(DawCacheEntry {
+                    daw: DawCacheReader::daw(self),
+                    file_name: file_name.to_string(),
+                    path: None,
+                    status: DawStatus::FailedValidation,
+                });
+            }
+        }
+        entries
+    }
+}
+
+impl DawCacheCleaner for ReaperCache {
+    fn daw(&self) -> &'static str {
+        "Reaper"
+    }
+
+    /// Drops the `[vstcache]` lines for removed plugins so Reaper doesn't list them
+    /// until its next rescan.
+    fn forget(&self, removed: &[PathBuf], backup: &mut CacheBackup) -> Result<usize> {
+        let removed: HashSet<String> = removed
+            .iter()
+            .filter_map(|p| p.file_name()?.to_str().map(cache_key))
+            .collect();
+        let mut count = 0;
+
+        for file in self.files.iter().filter(|f| f.is_file()) {
+            let content = std::fs::read_to_string(file)?;
+            let newline = if content.contains("\r\n") {
+                "\r\n"
+            } else {
+                "\n"
+            };
+            let mut in_cache = false;
+            let mut dropped = 0;
+
+            let kept: Vec<&str> = content
+                .lines()
+                .filter(|line| {
+                    let trimmed = line.trim();
+                    if trimmed.starts_with('[') {
+                        in_cache = trimmed.eq_ignore_ascii_case("[vstcache]");
+                        return true;
+                    }
+                    let forget = in_cache
+                        && trimmed
+                            .split_once('=')
+                            .is_some_and(|(name, _)| removed.contains(&cache_key(name)));
+                    if forget {
+                        dropped += 1;
+                    }
+                    !forget
+                })
+                .collect();
+
+            if dropped > 0 {
+                backup.save_copy(file)?;
+                let mut rewritten = kept.join(newline);
+                rewritten.push_str(newline);
+                std::fs::write(file, rewritten)?;
+                count += dropped;
+            }
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = "\
+[vstcache]
+Pro-Q_3.vst3=00A1B2C3D4E5F601,1234567890{ABCDEF},Pro-Q 3 (FabFilter)
+Broken_Synth.dll=00A1B2C3D4E5F602
+Waves_Shell.vst3=00A1B2C3D4E5F603,0,<SHELL>
+<SHELL>=00A1B2C3D4E5F604
+Crashy.vst3=00A1B2C3D4E5F605,
+[auplugins]
+Ignored.component=00A1B2C3D4E5F606
+";
+
+    fn reaper(files: Vec<PathBuf>) -> ReaperCache {
+        ReaperCache { files }
+    }
+
+    #[test]
+    fn plugins_without_an_id_failed_to_load() {
+        let names: Vec<_> = reaper(Vec::new())
+            .parse(FIXTURE)
+            .into_iter()
+            .map(|e| (e.file_name, e.path, e.status))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                (
+                    "Broken_Synth.dll".to_string(),
+                    None,
+                    DawStatus::FailedValidation
+                ),
+                ("Crashy.vst3".to_string(), None, DawStatus::FailedValidation),
+            ]
+        );
+    }
+
+    #[test]
+    fn forgetting_drops_only_removed_plugins() {
+        let dir = tempfile::tempdir().unwrap();
+        let ini = dir.path().join("reaper-vstplugins64.ini");
+        std::fs::write(&ini, FIXTURE.replace('\n', "\r\n")).unwrap();
+        let mut backup = CacheBackup::new_in(dir.path().join("backup"));
+
+        let removed = [PathBuf::from("/VST3/Broken Synth.dll")];
+        assert_eq!(
+            reaper(vec![ini.clone()])
+                .forget(&removed, &mut backup)
+                .unwrap(),
+            1
+        );
+
+        let content = std::fs::read_to_string(&ini).unwrap();
+        assert!(!content.contains("Broken_Synth"));
+        assert!(content.contains("Pro-Q_3.vst3="));
+        assert!(content.contains("\r\n[auplugins]\r\n"));
+
+        backup.restore().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&ini).unwrap(),
+            FIXTURE.replace('\n', "\r\n")
+        );
+    }
+}
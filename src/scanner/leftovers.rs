@@ -0,0 +1,232 @@
+use crate::plugin::normalize_name;
+use crate::utils::fs::disk_usage;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeftoverKind {
+    Presets,
+    SupportFiles,
+    Documents,
+    Caches,
+}
+
+impl LeftoverKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LeftoverKind::Presets => "Presets",
+            LeftoverKind::SupportFiles => "Support files",
+            LeftoverKind::Documents => "Documents",
+            LeftoverKind::Caches => "Caches",
+        }
+    }
+}
+
+/// A folder whose immediate children are named after vendors.
+#[derive(Debug, Clone)]
+pub struct LeftoverRoot {
+    pub path: PathBuf,
+    pub kind: LeftoverKind,
+}
+
+impl LeftoverRoot {
+    pub fn new(path: impl Into<PathBuf>, kind: LeftoverKind) -> Self {
+        Self {
+            path: path.into(),
+            kind,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Leftover {
+    pub path: PathBuf,
+    pub kind: LeftoverKind,
+    pub size: u64,
+    pub has_licence_files: bool,
+}
+
+/// Finds vendor-named folders in the usual support-file locations. Anything that
+/// contains one of `plugin_dirs` is skipped, so `Common Files/Avid` is never offered
+/// up as an Avid leftover while it still holds every vendor's AAX plugins.
+pub fn find_leftovers(manufacturer: &str, plugin_dirs: &[PathBuf]) -> Vec<Leftover> {
+    find_leftovers_in(manufacturer, &leftover_roots(), plugin_dirs)
+}
+
+pub fn find_leftovers_in(
+    manufacturer: &str,
+    roots: &[LeftoverRoot],
+    plugin_dirs: &[PathBuf],
+) -> Vec<Leftover> {
+    let wanted = normalize_name(manufacturer);
+    if wanted.len() < 3 || wanted == "unknown" {
+        return Vec::new();
+    }
+
+    let mut leftovers = Vec::new();
+    for root in roots {
+        let Ok(entries) = std::fs::read_dir(&root.path) else {
+            continue;
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let matches = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| normalize_name(n) == wanted);
+
+            let holds_plugins = plugin_dirs.iter().any(|dir| dir.starts_with(&path));
+
+            if matches && !holds_plugins && path.is_dir() {
+                leftovers.push(Leftover {
+                    size: disk_usage(&path),
+                    has_licence_files: contains_licence_files(&path),
+                    kind: root.kind,
+                    path,
+                });
+            }
+        }
+    }
+
+    leftovers.sort_by(|a, b| a.path.cmp(&b.path));
+    leftovers
+}
+
+fn contains_licence_files(dir: &Path) -> bool {
+    WalkDir::new(dir)
+        .max_depth(4)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .any(|e| is_licence_file(e.path()))
+}
+
+/// Files with a licence extension, or key and data files whose name says they hold a
+/// licence, such as `Serial.key`. A `.key` extension alone is also used by Keynote
+/// documents and SSH or sample-library keys, so it isn't enough by itself.
+pub(super) fn is_licence_file(path: &Path) -> bool {
+    const LICENCE_EXTENSIONS: [&str; 4] = ["lic", "license", "licence", "ilok"];
+    const NAMED_EXTENSIONS: [&str; 2] = ["key", "dat"];
+    const LICENCE_WORDS: [&str; 5] = ["licen", "serial", "activation", "authori", "registration"];
+
+    let Some(ext) = path.extension().and_then(|ext| ext.to_str()) else {
+        return false;
+    };
+    let is_one_of = |list: &[&str]| list.iter().any(|l| ext.eq_ignore_ascii_case(l));
+    if is_one_of(&LICENCE_EXTENSIONS) {
+        return true;
+    }
+    let stem = path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_lowercase();
+    is_one_of(&NAMED_EXTENSIONS) && LICENCE_WORDS.iter().any(|word| stem.contains(word))
+}
+
+#[cfg(target_os = "macos")]
+pub(super) fn leftover_roots() -> Vec<LeftoverRoot> {
+    super::macos::get_leftover_roots()
+}
+
+#[cfg(target_os = "windows")]
+pub(super) fn leftover_roots() -> Vec<LeftoverRoot> {
+    super::windows::get_leftover_roots()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub(super) fn leftover_roots() -> Vec<LeftoverRoot> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roots(dir: &Path) -> Vec<LeftoverRoot> {
+        vec![
+            LeftoverRoot::new(dir.join("Presets"), LeftoverKind::Presets),
+            LeftoverRoot::new(dir.join("Support"), LeftoverKind::SupportFiles),
+            LeftoverRoot::new(dir.join("Documents"), LeftoverKind::Documents),
+            LeftoverRoot::new(dir.join("Caches"), LeftoverKind::Caches),
+        ]
+    }
+
+    fn create(path: &Path, contents: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn licence_files_by_extension_or_name() {
+        for name in [
+            "FabFilter.lic",
+            "Pro-Q 3.LICENSE",
+            "Account.ilok",
+            "Serial.key",
+            "LicenseKey.key",
+            "activation.dat",
+        ] {
+            assert!(is_licence_file(Path::new(name)), "{}", name);
+        }
+        for name in [
+            "Talk.key",
+            "Library.key",
+            "presets.dat",
+            "License.txt",
+            "Licence",
+        ] {
+            assert!(!is_licence_file(Path::new(name)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn finds_vendor_folders_in_each_root() {
+        let dir = tempfile::tempdir().unwrap();
+        create(
+            &dir.path().join("Presets/FabFilter/Pro-Q 3/Vocal.ffp"),
+            "12345",
+        );
+        create(&dir.path().join("Support/Fab Filter/Serial.key"), "abc");
+        create(&dir.path().join("Support/FabFilterX/Other.txt"), "");
+        create(&dir.path().join("Presets/fabfilter.txt"), "");
+        create(&dir.path().join("Caches/FabFilter/Pro-Q 3.cache"), "");
+
+        let leftovers = find_leftovers_in("FabFilter", &roots(dir.path()), &[]);
+        let found: Vec<_> = leftovers
+            .iter()
+            .map(|l| (l.path.strip_prefix(dir.path()).unwrap(), l.kind))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (Path::new("Caches/FabFilter"), LeftoverKind::Caches),
+                (Path::new("Presets/FabFilter"), LeftoverKind::Presets),
+                (Path::new("Support/Fab Filter"), LeftoverKind::SupportFiles),
+            ]
+        );
+        assert!(!leftovers[1].has_licence_files);
+        assert!(leftovers[2].has_licence_files);
+    }
+
+    #[test]
+    fn skips_folders_that_hold_plugins() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugins = dir.path().join("Support/Avid/Audio/Plug-Ins");
+        create(&plugins.join("Reverb.aaxplugin/binary"), "");
+
+        let found = find_leftovers_in("Avid", &roots(dir.path()), std::slice::from_ref(&plugins));
+        assert!(found.is_empty());
+        assert_eq!(find_leftovers_in("Avid", &roots(dir.path()), &[]).len(), 1);
+    }
+
+    #[test]
+    fn ignores_short_and_unknown_vendors() {
+        let dir = tempfile::tempdir().unwrap();
+        create(&dir.path().join("Presets/UA/preset"), "");
+        create(&dir.path().join("Presets/Unknown/preset"), "");
+
+        assert!(find_leftovers_in("UA", &roots(dir.path()), &[]).is_empty());
+        assert!(find_leftovers_in("Unknown", &roots(dir.path()), &[]).is_empty());
+    }
+}
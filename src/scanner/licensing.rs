@@ -0,0 +1,398 @@
+//! Detectors for how vendors license their plugins, from files on disk alone. Nothing
+//! here contacts a licence server or reads a licence's contents.
+
+use super::leftovers::{is_licence_file, leftover_roots, LeftoverKind};
+use crate::plugin::{normalize_name, Plugin};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LicenceMechanism {
+    ILok,
+    NativeAccess,
+    WavesCentral,
+    LicenceFiles,
+}
+
+impl LicenceMechanism {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LicenceMechanism::ILok => "iLok",
+            LicenceMechanism::NativeAccess => "Native Access",
+            LicenceMechanism::WavesCentral => "Waves Central",
+            LicenceMechanism::LicenceFiles => "Licence files",
+        }
+    }
+
+    /// What finding the mechanism means, for a tooltip.
+    pub fn description(&self) -> &'static str {
+        match self {
+            LicenceMechanism::ILok => {
+                "PACE's iLok support folder has entries for this vendor; authorizations \
+                 live on an iLok or in iLok Cloud"
+            }
+            LicenceMechanism::NativeAccess => {
+                "Products are registered with Native Access; reactivate them there"
+            }
+            LicenceMechanism::WavesCentral => {
+                "Licences are managed by Waves Central; move them to the cloud or a USB \
+                 drive before migrating"
+            }
+            LicenceMechanism::LicenceFiles => {
+                "Licence or key files in the vendor's support folder; copy them along"
+            }
+        }
+    }
+}
+
+/// One way a vendor's plugins are licensed, with the files that show it.
+#[derive(Debug, Clone)]
+pub struct LicenceFootprint {
+    pub mechanism: LicenceMechanism,
+    pub evidence: Vec<PathBuf>,
+}
+
+/// Looks for one licensing mechanism. The paths it checks are fields so detectors
+/// can be pointed at fixture folders; `system()` constructors fill in the real ones.
+pub trait LicenceDetector {
+    fn mechanism(&self) -> LicenceMechanism;
+
+    /// Files showing `vendor` uses this mechanism, or nothing when it doesn't appear to.
+    fn evidence(&self, vendor: &str, plugins: &[Plugin]) -> Vec<PathBuf>;
+}
+
+fn existing(paths: &[PathBuf]) -> Vec<PathBuf> {
+    paths.iter().filter(|p| p.exists()).cloned().collect()
+}
+
+/// The vendor's normalized name, or nothing when it's too short or generic to match
+/// folder names against.
+fn vendor_key(vendor: &str) -> Option<String> {
+    let key = normalize_name(vendor);
+    (key.len() >= 3 && key != "unknown").then_some(key)
+}
+
+/// Vendors licensing through iLok, found by their entries in PACE's support folders.
+/// PACE being installed isn't enough on its own: every AAX plugin is PACE-signed,
+/// whatever licensing its vendor uses.
+pub struct PaceDetector {
+    pub support_dirs: Vec<PathBuf>,
+}
+
+impl PaceDetector {
+    #[cfg(target_os = "macos")]
+    pub fn system() -> Self {
+        Self {
+            support_dirs: vec![PathBuf::from("/Library/Application Support/PACEAntiPiracy")],
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn system() -> Self {
+        let mut support_dirs = Vec::new();
+        if let Some(dir) = std::env::var_os("ProgramData") {
+            support_dirs.push(PathBuf::from(dir).join("PACE"));
+        }
+        for var in ["ProgramW6432", "ProgramFiles(x86)"] {
+            if let Some(dir) = std::env::var_os(var) {
+                support_dirs.push(PathBuf::from(dir).join("Common Files").join("PACE"));
+            }
+        }
+        Self { support_dirs }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    pub fn system() -> Self {
+        Self {
+            support_dirs: Vec::new(),
+        }
+    }
+}
+
+impl LicenceDetector for PaceDetector {
+    fn mechanism(&self) -> LicenceMechanism {
+        LicenceMechanism::ILok
+    }
+
+    fn evidence(&self, vendor: &str, _plugins: &[Plugin]) -> Vec<PathBuf> {
+        let Some(wanted) = vendor_key(vendor) else {
+            return Vec::new();
+        };
+        self.support_dirs
+            .iter()
+            .flat_map(|dir| WalkDir::new(dir).min_depth(1).max_depth(3))
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                e.path()
+                    .file_stem()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| normalize_name(n).contains(&wanted))
+            })
+            .map(|e| e.into_path())
+            .collect()
+    }
+}
+
+/// Native Instruments' Service Center folder, which holds one registration file per
+/// product, and the Native Access app.
+pub struct NativeAccessDetector {
+    pub paths: Vec<PathBuf>,
+}
+
+impl NativeAccessDetector {
+    #[cfg(target_os = "macos")]
+    pub fn system() -> Self {
+        Self {
+            paths: vec![
+                PathBuf::from("/Library/Application Support/Native Instruments/Service Center"),
+                PathBuf::from("/Applications/Native Access.app"),
+            ],
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn system() -> Self {
+        let mut paths = Vec::new();
+        for var in ["ProgramW6432", "ProgramFiles(x86)"] {
+            if let Some(dir) = std::env::var_os(var) {
+                let dir = PathBuf::from(dir);
+                paths.push(dir.join("Common Files/Native Instruments/Service Center"));
+                paths.push(dir.join("Native Instruments/Native Access"));
+            }
+        }
+        Self { paths }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    pub fn system() -> Self {
+        Self { paths: Vec::new() }
+    }
+}
+
+impl LicenceDetector for NativeAccessDetector {
+    fn mechanism(&self) -> LicenceMechanism {
+        LicenceMechanism::NativeAccess
+    }
+
+    fn evidence(&self, vendor: &str, _plugins: &[Plugin]) -> Vec<PathBuf> {
+        if normalize_name(vendor) != "nativeinstruments" {
+            return Vec::new();
+        }
+        existing(&self.paths)
+    }
+}
+
+/// Waves' licence folder and the Waves Central app that manages it.
+pub struct WavesCentralDetector {
+    pub paths: Vec<PathBuf>,
+}
+
+impl WavesCentralDetector {
+    #[cfg(target_os = "macos")]
+    pub fn system() -> Self {
+        Self {
+            paths: vec![
+                PathBuf::from("/Library/Application Support/Waves/Licenses"),
+                PathBuf::from("/Applications/Waves Central.app"),
+            ],
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn system() -> Self {
+        let mut paths = Vec::new();
+        if let Some(dir) = std::env::var_os("ProgramData") {
+            paths.push(PathBuf::from(dir).join("Waves Audio").join("Licenses"));
+        }
+        for var in ["ProgramW6432", "ProgramFiles(x86)"] {
+            if let Some(dir) = std::env::var_os(var) {
+                paths.push(PathBuf::from(dir).join("Waves Central"));
+            }
+        }
+        Self { paths }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    pub fn system() -> Self {
+        Self { paths: Vec::new() }
+    }
+}
+
+impl LicenceDetector for WavesCentralDetector {
+    fn mechanism(&self) -> LicenceMechanism {
+        LicenceMechanism::WavesCentral
+    }
+
+    fn evidence(&self, vendor: &str, _plugins: &[Plugin]) -> Vec<PathBuf> {
+        if !normalize_name(vendor).starts_with("waves") {
+            return Vec::new();
+        }
+        existing(&self.paths)
+    }
+}
+
+/// Licence and key files (`.lic`, `.key`, `.ilok` and the like) in a folder named
+/// after the vendor in one of the support-file locations, such as
+/// `Application Support/<Vendor>`.
+pub struct LicenceFileDetector {
+    pub roots: Vec<PathBuf>,
+}
+
+impl LicenceFileDetector {
+    pub fn system() -> Self {
+        Self {
+            roots: leftover_roots()
+                .into_iter()
+                .filter(|root| root.kind == LeftoverKind::SupportFiles)
+                .map(|root| root.path)
+                .collect(),
+        }
+    }
+}
+
+impl LicenceDetector for LicenceFileDetector {
+    fn mechanism(&self) -> LicenceMechanism {
+        LicenceMechanism::LicenceFiles
+    }
+
+    fn evidence(&self, vendor: &str, _plugins: &[Plugin]) -> Vec<PathBuf> {
+        let Some(wanted) = vendor_key(vendor) else {
+            return Vec::new();
+        };
+
+        let mut files = Vec::new();
+        for root in &self.roots {
+            let Ok(entries) = std::fs::read_dir(root) else {
+                continue;
+            };
+            for entry in entries.filter_map(|e| e.ok()) {
+                let path = entry.path();
+                let matches = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| normalize_name(n) == wanted);
+                if matches && path.is_dir() {
+                    files.extend(licence_files(&path));
+                }
+            }
+        }
+        files
+    }
+}
+
+fn licence_files(dir: &Path) -> Vec<PathBuf> {
+    WalkDir::new(dir)
+        .max_depth(4)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && is_licence_file(e.path()))
+        .map(|e| e.into_path())
+        .collect()
+}
+
+pub fn system_detectors() -> Vec<Box<dyn LicenceDetector>> {
+    vec![
+        Box::new(PaceDetector::system()),
+        Box::new(NativeAccessDetector::system()),
+        Box::new(WavesCentralDetector::system()),
+        Box::new(LicenceFileDetector::system()),
+    ]
+}
+
+/// Every mechanism the detectors find for a vendor, in detector order.
+pub fn detect_licensing(
+    detectors: &[Box<dyn LicenceDetector>],
+    vendor: &str,
+    plugins: &[Plugin],
+) -> Vec<LicenceFootprint> {
+    detectors
+        .iter()
+        .filter_map(|detector| {
+            let evidence = detector.evidence(vendor, plugins);
+            (!evidence.is_empty()).then(|| LicenceFootprint {
+                mechanism: detector.mechanism(),
+                evidence,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn touch(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"").unwrap();
+    }
+
+    #[test]
+    fn pace_needs_an_entry_for_the_vendor() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(&dir.path().join("License Support/Sound Toys/rack.txt"));
+        touch(&dir.path().join("License Support/Other Vendor/product.txt"));
+
+        let detector = PaceDetector {
+            support_dirs: vec![dir.path().to_path_buf()],
+        };
+        let evidence = detector.evidence("SoundToys", &[]);
+        assert_eq!(evidence, [dir.path().join("License Support/Sound Toys")]);
+        assert!(detector.evidence("FabFilter", &[]).is_empty());
+        assert!(detector.evidence("Unknown", &[]).is_empty());
+    }
+
+    #[test]
+    fn vendor_apps_only_count_for_their_vendor() {
+        let dir = tempfile::tempdir().unwrap();
+        let service_center = dir.path().join("Service Center");
+        fs::create_dir(&service_center).unwrap();
+        let missing = dir.path().join("Native Access.app");
+
+        let native_access = NativeAccessDetector {
+            paths: vec![service_center.clone(), missing],
+        };
+        assert_eq!(
+            native_access.evidence("Native Instruments", &[]),
+            vec![service_center.clone()]
+        );
+        assert!(native_access.evidence("Waves", &[]).is_empty());
+
+        let waves = WavesCentralDetector {
+            paths: vec![service_center.clone()],
+        };
+        assert_eq!(waves.evidence("Waves Audio", &[]), [service_center]);
+        assert!(waves.evidence("Native Instruments", &[]).is_empty());
+    }
+
+    #[test]
+    fn licence_files_in_the_vendor_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let licence = dir.path().join("Valhalla DSP/Licenses/valhalla.lic");
+        touch(&licence);
+        touch(&dir.path().join("Valhalla DSP/Presets/hall.vpreset"));
+        touch(&dir.path().join("Other/other.lic"));
+
+        let detector = LicenceFileDetector {
+            roots: vec![dir.path().to_path_buf()],
+        };
+        assert_eq!(detector.evidence("Valhalla DSP", &[]), [licence]);
+        assert!(detector.evidence("Xfer Records", &[]).is_empty());
+    }
+
+    #[test]
+    fn detect_licensing_lists_only_mechanisms_with_evidence() {
+        let dir = tempfile::tempdir().unwrap();
+        let detectors: Vec<Box<dyn LicenceDetector>> = vec![
+            Box::new(PaceDetector {
+                support_dirs: vec![dir.path().to_path_buf()],
+            }),
+            Box::new(WavesCentralDetector {
+                paths: vec![dir.path().to_path_buf()],
+            }),
+        ];
+        let footprints = detect_licensing(&detectors, "Waves", &[]);
+        assert_eq!(footprints.len(), 1);
+        assert_eq!(footprints[0].mechanism, LicenceMechanism::WavesCentral);
+    }
+}
@@ -6,6 +6,7 @@ use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use super::leftovers::{LeftoverKind, LeftoverRoot};
 use super::metadata::parse_au_plugin;
 
 pub(super) fn get_vst2_paths() -> Vec<PathBuf> {
@@ -45,6 +46,40 @@ pub(super) fn get_au_paths() -> Vec<PathBuf> {
     paths
 }
 
+pub(super) fn get_leftover_roots() -> Vec<LeftoverRoot> {
+    let mut roots = vec![
+        LeftoverRoot::new("/Library/Audio/Presets", LeftoverKind::Presets),
+        LeftoverRoot::new("/Library/Application Support", LeftoverKind::SupportFiles),
+        LeftoverRoot::new("/Users/Shared", LeftoverKind::SupportFiles),
+    ];
+    if let Some(home) = dirs::home_dir() {
+        roots.push(LeftoverRoot::new(
+            home.join("Library/Audio/Presets"),
+            LeftoverKind::Presets,
+        ));
+        roots.push(LeftoverRoot::new(
+            home.join("Library/Application Support"),
+            LeftoverKind::SupportFiles,
+        ));
+        roots.push(LeftoverRoot::new(
+            home.join("Library/Caches"),
+            LeftoverKind::Caches,
+        ));
+        roots.push(LeftoverRoot::new(
+            home.join("Documents"),
+            LeftoverKind::Documents,
+        ));
+    }
+    roots
+}
+
+pub(super) fn get_receipt_dirs() -> Vec<PathBuf> {
+    vec![
+        PathBuf::from("/var/db/receipts"),
+        PathBuf::from("/Library/Receipts"),
+    ]
+}
+
 pub(super) fn scan_au_directory(dir: &Path) -> Result<Vec<Plugin>> {
     let mut plugins = Vec::new();
     for entry in WalkDir::new(dir)
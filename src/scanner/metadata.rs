@@ -1,7 +1,10 @@
-use crate::plugin::{Plugin, PluginType};
+use crate::plugin::{CodeSignature, ManufacturerSource, Plugin, PluginType};
 use crate::utils::error::Result;
+use crate::utils::fs::disk_usage;
 use std::path::Path;
 
+#[cfg(target_os = "windows")]
+use crate::plugin::clean_manufacturer_name;
 #[cfg(target_os = "windows")]
 use walkdir::WalkDir;
 
@@ -21,6 +24,95 @@ use winapi::shared::minwindef::UINT;
 #[cfg(target_os = "windows")]
 use winapi::um::winver::{GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW};
 
+#[derive(Debug, Default)]
+struct PluginMetadata {
+    name: Option<String>,
+    manufacturer: Option<String>,
+    version: Option<String>,
+    bundle_id: Option<String>,
+    manufacturer_code: Option<String>,
+    copyright: Option<String>,
+}
+
+impl PluginMetadata {
+    fn into_plugin(self, path: &Path, default_name: String, plugin_type: PluginType) -> Plugin {
+        let signature = bundle_signature(path);
+        let (manufacturer, manufacturer_source) = match self.manufacturer {
+            Some(manufacturer) => (manufacturer, ManufacturerSource::Metadata),
+            None => match signed_company(signature.as_ref()) {
+                Some(publisher) => (publisher, ManufacturerSource::CodeSigning),
+                None => ("Unknown".to_string(), ManufacturerSource::Unknown),
+            },
+        };
+
+        Plugin {
+            name: self.name.unwrap_or(default_name),
+            manufacturer,
+            manufacturer_source,
+            version: self.version,
+            bundle_id: self.bundle_id,
+            manufacturer_code: self.manufacturer_code,
+            copyright: self.copyright,
+            signature,
+            path: path.to_path_buf(),
+            plugin_type,
+            size: disk_usage(path),
+            disabled: false,
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn bundle_signature(path: &Path) -> Option<CodeSignature> {
+    Some(super::codesign::inspect_bundle(path))
+}
+
+/// Bundled VST3 and AAX plugins keep their DLL under `Contents`, e.g.
+/// `Contents/x86_64-win/Foo.vst3` or `Contents/x64/Foo.aaxplugin`.
+#[cfg(target_os = "windows")]
+fn bundle_signature(path: &Path) -> Option<CodeSignature> {
+    let binary = if path.is_file() {
+        Some(path.to_path_buf())
+    } else {
+        WalkDir::new(path.join("Contents"))
+            .max_depth(3)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .map(|e| e.into_path())
+            .find(|p| {
+                p.is_file()
+                    && p.extension().is_some_and(|ext| {
+                        ["dll", "vst3", "aaxplugin", "aax"]
+                            .iter()
+                            .any(|e| ext.eq_ignore_ascii_case(e))
+                    })
+            })
+    };
+
+    Some(binary.map_or_else(CodeSignature::unsigned, |binary| {
+        super::authenticode::inspect_file(&binary)
+    }))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn bundle_signature(_path: &Path) -> Option<CodeSignature> {
+    None
+}
+
+/// A DLL without a `CompanyName` is attributed to its Authenticode publisher, the
+/// Windows counterpart of the vendor a Mac bundle declares.
+#[cfg(target_os = "windows")]
+fn signed_company(signature: Option<&CodeSignature>) -> Option<String> {
+    signature?
+        .publisher()
+        .map(|publisher| clean_manufacturer_name(&publisher))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn signed_company(_signature: Option<&CodeSignature>) -> Option<String> {
+    None
+}
+
 #[cfg(target_os = "windows")]
 #[repr(C)]
 struct VsFixedFileInfo {
@@ -50,40 +142,20 @@ pub(super) fn parse_vst2_plugin(path: &Path) -> Result<Plugin> {
     {
         let info_plist_path = path.join("Contents/Info.plist");
         if info_plist_path.exists() {
-            if let Ok((parsed_name, parsed_manufacturer, version)) =
-                parse_info_plist(&info_plist_path)
-            {
-                return Ok(Plugin {
-                    name: parsed_name.unwrap_or(default_name),
-                    manufacturer: parsed_manufacturer.unwrap_or_else(|| "Unknown".to_string()),
-                    version,
-                    path: path.to_path_buf(),
-                    plugin_type: PluginType::VST2,
-                });
+            if let Ok(metadata) = parse_info_plist(&info_plist_path) {
+                return Ok(metadata.into_plugin(path, default_name, PluginType::VST2));
             }
         }
     }
 
     #[cfg(target_os = "windows")]
     {
-        if let Ok((name, manufacturer, version)) = parse_windows_dll_metadata(path) {
-            return Ok(Plugin {
-                name: name.unwrap_or(default_name),
-                manufacturer: manufacturer.unwrap_or_else(|| "Unknown".to_string()),
-                version,
-                path: path.to_path_buf(),
-                plugin_type: PluginType::VST2,
-            });
+        if let Ok(metadata) = parse_windows_dll_metadata(path) {
+            return Ok(metadata.into_plugin(path, default_name, PluginType::VST2));
         }
     }
 
-    Ok(Plugin {
-        name: default_name,
-        manufacturer: "Unknown".to_string(),
-        version: None,
-        path: path.to_path_buf(),
-        plugin_type: PluginType::VST2,
-    })
+    Ok(PluginMetadata::default().into_plugin(path, default_name, PluginType::VST2))
 }
 
 pub(super) fn parse_vst3_plugin(path: &Path) -> Result<Plugin> {
@@ -97,16 +169,8 @@ pub(super) fn parse_vst3_plugin(path: &Path) -> Result<Plugin> {
     {
         let info_plist_path = path.join("Contents/Info.plist");
         if info_plist_path.exists() {
-            if let Ok((parsed_name, parsed_manufacturer, version)) =
-                parse_info_plist(&info_plist_path)
-            {
-                return Ok(Plugin {
-                    name: parsed_name.unwrap_or(default_name),
-                    manufacturer: parsed_manufacturer.unwrap_or_else(|| "Unknown".to_string()),
-                    version,
-                    path: path.to_path_buf(),
-                    plugin_type: PluginType::VST3,
-                });
+            if let Ok(metadata) = parse_info_plist(&info_plist_path) {
+                return Ok(metadata.into_plugin(path, default_name, PluginType::VST3));
             }
         }
     }
@@ -114,14 +178,8 @@ pub(super) fn parse_vst3_plugin(path: &Path) -> Result<Plugin> {
     #[cfg(target_os = "windows")]
     {
         if path.is_file() {
-            if let Ok((name, manufacturer, version)) = parse_windows_dll_metadata(path) {
-                return Ok(Plugin {
-                    name: name.unwrap_or(default_name),
-                    manufacturer: manufacturer.unwrap_or_else(|| "Unknown".to_string()),
-                    version,
-                    path: path.to_path_buf(),
-                    plugin_type: PluginType::VST3,
-                });
+            if let Ok(metadata) = parse_windows_dll_metadata(path) {
+                return Ok(metadata.into_plugin(path, default_name, PluginType::VST3));
             }
         }
 
@@ -133,35 +191,13 @@ pub(super) fn parse_vst3_plugin(path: &Path) -> Result<Plugin> {
                         if let Ok(json_value) =
                             serde_json::from_str::<serde_json::Value>(&file_content)
                         {
-                            let name = json_value
-                                .get("Name")
-                                .or_else(|| json_value.get("name"))
-                                .and_then(|v| v.as_str())
-                                .map(|s| s.to_string());
-
-                            let manufacturer = json_value
-                                .get("Vendor")
-                                .or_else(|| json_value.get("vendor"))
-                                .or_else(|| json_value.get("Company"))
-                                .or_else(|| json_value.get("company"))
-                                .and_then(|v| v.as_str())
-                                .map(|s| s.to_string());
-
-                            let version = json_value
-                                .get("Version")
-                                .or_else(|| json_value.get("version"))
-                                .and_then(|v| v.as_str())
-                                .map(|s| s.to_string());
-
-                            if name.is_some() || manufacturer.is_some() {
-                                return Ok(Plugin {
-                                    name: name.unwrap_or_else(|| default_name.clone()),
-                                    manufacturer: manufacturer
-                                        .unwrap_or_else(|| "Unknown".to_string()),
-                                    version,
-                                    path: path.to_path_buf(),
-                                    plugin_type: PluginType::VST3,
-                                });
+                            let metadata = parse_vst3_module_info(&json_value);
+                            if metadata.name.is_some() || metadata.manufacturer.is_some() {
+                                return Ok(metadata.into_plugin(
+                                    path,
+                                    default_name,
+                                    PluginType::VST3,
+                                ));
                             }
                         }
                     }
@@ -181,17 +217,12 @@ pub(super) fn parse_vst3_plugin(path: &Path) -> Result<Plugin> {
                             if ext.eq_ignore_ascii_case("vst3")
                                 || ext.eq_ignore_ascii_case("dll")
                             {
-                                if let Ok((name, manufacturer, version)) =
-                                    parse_windows_dll_metadata(entry_path)
-                                {
-                                    return Ok(Plugin {
-                                        name: name.unwrap_or_else(|| default_name.clone()),
-                                        manufacturer: manufacturer
-                                            .unwrap_or_else(|| "Unknown".to_string()),
-                                        version,
-                                        path: path.to_path_buf(),
-                                        plugin_type: PluginType::VST3,
-                                    });
+                                if let Ok(metadata) = parse_windows_dll_metadata(entry_path) {
+                                    return Ok(metadata.into_plugin(
+                                        path,
+                                        default_name,
+                                        PluginType::VST3,
+                                    ));
                                 }
                             }
                         }
@@ -201,13 +232,7 @@ pub(super) fn parse_vst3_plugin(path: &Path) -> Result<Plugin> {
         }
     }
 
-    Ok(Plugin {
-        name: default_name,
-        manufacturer: "Unknown".to_string(),
-        version: None,
-        path: path.to_path_buf(),
-        plugin_type: PluginType::VST3,
-    })
+    Ok(PluginMetadata::default().into_plugin(path, default_name, PluginType::VST3))
 }
 
 pub(super) fn parse_aax_plugin(path: &Path) -> Result<Plugin> {
@@ -221,16 +246,8 @@ pub(super) fn parse_aax_plugin(path: &Path) -> Result<Plugin> {
     {
         let info_plist_path = path.join("Contents/Info.plist");
         if info_plist_path.exists() {
-            if let Ok((parsed_name, parsed_manufacturer, version)) =
-                parse_info_plist(&info_plist_path)
-            {
-                return Ok(Plugin {
-                    name: parsed_name.unwrap_or(default_name),
-                    manufacturer: parsed_manufacturer.unwrap_or_else(|| "Unknown".to_string()),
-                    version,
-                    path: path.to_path_buf(),
-                    plugin_type: PluginType::AAX,
-                });
+            if let Ok(metadata) = parse_info_plist(&info_plist_path) {
+                return Ok(metadata.into_plugin(path, default_name, PluginType::AAX));
             }
         }
     }
@@ -252,17 +269,12 @@ pub(super) fn parse_aax_plugin(path: &Path) -> Result<Plugin> {
                                 || ext.eq_ignore_ascii_case("aax")
                                 || ext.eq_ignore_ascii_case("dll")
                             {
-                                if let Ok((name, manufacturer, version)) =
-                                    parse_windows_dll_metadata(entry_path)
-                                {
-                                    return Ok(Plugin {
-                                        name: name.unwrap_or_else(|| default_name.clone()),
-                                        manufacturer: manufacturer
-                                            .unwrap_or_else(|| "Unknown".to_string()),
-                                        version,
-                                        path: path.to_path_buf(),
-                                        plugin_type: PluginType::AAX,
-                                    });
+                                if let Ok(metadata) = parse_windows_dll_metadata(entry_path) {
+                                    return Ok(metadata.into_plugin(
+                                        path,
+                                        default_name,
+                                        PluginType::AAX,
+                                    ));
                                 }
                             }
                         }
@@ -270,25 +282,13 @@ pub(super) fn parse_aax_plugin(path: &Path) -> Result<Plugin> {
                 }
             }
         } else if path.is_file() {
-            if let Ok((name, manufacturer, version)) = parse_windows_dll_metadata(path) {
-                return Ok(Plugin {
-                    name: name.unwrap_or(default_name),
-                    manufacturer: manufacturer.unwrap_or_else(|| "Unknown".to_string()),
-                    version,
-                    path: path.to_path_buf(),
-                    plugin_type: PluginType::AAX,
-                });
+            if let Ok(metadata) = parse_windows_dll_metadata(path) {
+                return Ok(metadata.into_plugin(path, default_name, PluginType::AAX));
             }
         }
     }
 
-    Ok(Plugin {
-        name: default_name,
-        manufacturer: "Unknown".to_string(),
-        version: None,
-        path: path.to_path_buf(),
-        plugin_type: PluginType::AAX,
-    })
+    Ok(PluginMetadata::default().into_plugin(path, default_name, PluginType::AAX))
 }
 
 #[cfg(target_os = "macos")]
@@ -301,31 +301,16 @@ pub(super) fn parse_au_plugin(path: &Path) -> Result<Plugin> {
 
     let info_plist_path = path.join("Contents/Info.plist");
     if info_plist_path.exists() {
-        if let Ok((parsed_name, parsed_manufacturer, version)) = parse_info_plist(&info_plist_path)
-        {
-            return Ok(Plugin {
-                name: parsed_name.unwrap_or(default_name),
-                manufacturer: parsed_manufacturer.unwrap_or_else(|| "Unknown".to_string()),
-                version,
-                path: path.to_path_buf(),
-                plugin_type: PluginType::AU,
-            });
+        if let Ok(metadata) = parse_info_plist(&info_plist_path) {
+            return Ok(metadata.into_plugin(path, default_name, PluginType::AU));
         }
     }
 
-    Ok(Plugin {
-        name: default_name,
-        manufacturer: "Unknown".to_string(),
-        version: None,
-        path: path.to_path_buf(),
-        plugin_type: PluginType::AU,
-    })
+    Ok(PluginMetadata::default().into_plugin(path, default_name, PluginType::AU))
 }
 
 #[cfg(target_os = "macos")]
-fn parse_info_plist(
-    plist_path: &Path,
-) -> Result<(Option<String>, Option<String>, Option<String>)> {
+fn parse_info_plist(plist_path: &Path) -> Result<PluginMetadata> {
     let plist_data = std::fs::read(plist_path)?;
     let plist: Value =
         plist::from_bytes(&plist_data).context("Failed to parse plist from bytes")?;
@@ -335,9 +320,14 @@ fn parse_info_plist(
 
     let mut name: Option<String> = None;
     let mut manufacturer: Option<String> = None;
+    let mut manufacturer_code: Option<String> = None;
 
     if let Some(components) = root_dict.get("AudioComponents").and_then(Value::as_array) {
         if let Some(component_dict) = components.get(0).and_then(Value::as_dictionary) {
+            manufacturer_code = component_dict
+                .get("manufacturer")
+                .and_then(Value::as_string)
+                .map(str::to_string);
             if let Some(full_name) = component_dict.get("name").and_then(Value::as_string) {
                 if let Some((manuf, plug_name)) = full_name.split_once(':') {
                     manufacturer = Some(manuf.trim().to_string());
@@ -355,19 +345,15 @@ fn parse_info_plist(
             .map(str::to_string);
     }
 
-    if manufacturer.is_none() {
-        manufacturer = root_dict
-            .get("CFBundleIdentifier")
-            .and_then(Value::as_string)
-            .and_then(|id| {
-                let parts: Vec<&str> = id.split('.').collect();
-                if parts.len() >= 2 {
-                    Some(parts[1].to_string())
-                } else {
-                    None
-                }
-            });
-    }
+    let bundle_id = root_dict
+        .get("CFBundleIdentifier")
+        .and_then(Value::as_string)
+        .map(str::to_string);
+
+    let copyright = root_dict
+        .get("NSHumanReadableCopyright")
+        .and_then(Value::as_string)
+        .map(str::to_string);
 
     let version = root_dict
         .get("CFBundleShortVersionString")
@@ -375,13 +361,60 @@ fn parse_info_plist(
         .and_then(Value::as_string)
         .map(str::to_string);
 
-    Ok((name, manufacturer, version))
+    Ok(PluginMetadata {
+        name,
+        manufacturer,
+        version,
+        bundle_id,
+        manufacturer_code,
+        copyright,
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn parse_vst3_module_info(json_value: &serde_json::Value) -> PluginMetadata {
+    let name = json_value
+        .get("Name")
+        .or_else(|| json_value.get("name"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    let manufacturer = json_value
+        .get("Vendor")
+        .or_else(|| json_value.get("vendor"))
+        .or_else(|| json_value.get("Company"))
+        .or_else(|| json_value.get("company"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    let version = json_value
+        .get("Version")
+        .or_else(|| json_value.get("version"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    // moduleinfo.json lists each exported class; the first class ID is as good an
+    // identity as a macOS bundle identifier when matching copies of the same plugin.
+    let bundle_id = json_value
+        .get("Classes")
+        .and_then(|v| v.as_array())
+        .and_then(|classes| classes.first())
+        .and_then(|class| class.get("CID"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    PluginMetadata {
+        name,
+        manufacturer,
+        version,
+        bundle_id,
+        manufacturer_code: None,
+        copyright: None,
+    }
 }
 
 #[cfg(target_os = "windows")]
-fn parse_windows_dll_metadata(
-    path: &Path,
-) -> Result<(Option<String>, Option<String>, Option<String>)> {
+fn parse_windows_dll_metadata(path: &Path) -> Result<PluginMetadata> {
     let path_wide: Vec<u16> = OsStr::new(path)
         .encode_wide()
         .chain(std::iter::once(0))
@@ -443,10 +476,18 @@ fn parse_windows_dll_metadata(
         let product_name = get_value("ProductName");
         let company_name = get_value("CompanyName");
         let file_description = get_value("FileDescription");
+        let copyright = get_value("LegalCopyright");
 
         let name = product_name.or(file_description);
 
-        Ok((name, company_name, version))
+        Ok(PluginMetadata {
+            name,
+            manufacturer: company_name,
+            version,
+            bundle_id: None,
+            manufacturer_code: None,
+            copyright,
+        })
     }
 }
 
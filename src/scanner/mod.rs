@@ -1,6 +1,29 @@
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+mod authenticode;
+mod bom;
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+mod codesign;
 mod core;
+mod daw;
+mod leftovers;
+mod licensing;
 mod macos;
 mod metadata;
+mod projects;
+mod receipts;
+mod uninstallers;
 mod windows;
+mod x509;
 
-pub use self::core::PluginScanner;
+pub use self::core::{disabled_directory, PluginScanner};
+pub use self::daw::{
+    clear_daw_caches, read_daw_caches, system_cleaners, system_readers, CacheBackup,
+    DawCacheCleaner, DawCacheEntry, DawCacheReader,
+};
+pub use self::leftovers::{find_leftovers, Leftover};
+pub use self::licensing::{detect_licensing, system_detectors, LicenceDetector, LicenceFootprint};
+pub use self::projects::{project_usage, scan_projects, Project, ProjectUsage};
+pub use self::receipts::{InstalledPackage, ReceiptIndex};
+pub use self::uninstallers::{
+    find_uninstaller, launch_uninstaller, system_registry, UninstallEntry, UninstallRegistry,
+};
@@ -0,0 +1,380 @@
+use crate::plugin::{normalize_name, Plugin};
+use crate::utils::error::Result;
+use flate2::read::GzDecoder;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+static ALS_PLUGIN_INFO: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?s)<(VstPluginInfo|Vst3PluginInfo|AuPluginInfo)\b.*?</(?:VstPluginInfo|Vst3PluginInfo|AuPluginInfo)>")
+        .unwrap()
+});
+static ALS_VALUE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"<(PlugName|Name|FileName|Path|UniqueId)\s+Value="([^"]*)""#).unwrap()
+});
+static RPP_TOKEN: Lazy<Regex> = Lazy::new(|| Regex::new(r#""([^"]*)"|(\S+)"#).unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProjectKind {
+    Reaper,
+    Ableton,
+}
+
+/// A plugin as a project refers to it. Which fields are present depends on the DAW
+/// and the plugin format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginReference {
+    pub name: String,
+    pub file_name: Option<String>,
+    /// VST2 unique ID or VST3 class ID.
+    pub id: Option<String>,
+}
+
+impl PluginReference {
+    /// A recorded file name is matched exactly (ignoring case) and a recorded class ID
+    /// against the plugin's identifier; otherwise the display names have to match.
+    pub fn matches(&self, plugin: &Plugin) -> bool {
+        if let (Some(id), Some(bundle_id)) = (&self.id, &plugin.bundle_id) {
+            let bundle_id = normalize_name(bundle_id);
+            if bundle_id.len() >= 8 && normalize_name(id).contains(&bundle_id) {
+                return true;
+            }
+        }
+        if let Some(file_name) = &self.file_name {
+            let plugin_file = plugin.path.file_name().and_then(|n| n.to_str());
+            if plugin_file.is_some_and(|n| n.eq_ignore_ascii_case(file_name)) {
+                return true;
+            }
+        }
+        let name = normalize_name(&self.name);
+        !name.is_empty() && name == normalize_name(&plugin.name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub path: PathBuf,
+    pub modified: Option<SystemTime>,
+    pub plugins: Vec<PluginReference>,
+}
+
+impl Project {
+    pub fn name(&self) -> String {
+        self.path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    pub fn uses(&self, plugin: &Plugin) -> bool {
+        self.plugins.iter().any(|r| r.matches(plugin))
+    }
+}
+
+/// How often a plugin turns up in the scanned projects.
+#[derive(Debug, Clone)]
+pub struct ProjectUsage {
+    pub project_count: usize,
+    /// The most recently saved project using the plugin.
+    pub last_project: String,
+}
+
+pub fn project_usage(projects: &[Project], plugin: &Plugin) -> Option<ProjectUsage> {
+    let using: Vec<&Project> = projects.iter().filter(|p| p.uses(plugin)).collect();
+    let last = using.iter().max_by_key(|p| p.modified)?;
+    Some(ProjectUsage {
+        project_count: using.len(),
+        last_project: last.name(),
+    })
+}
+
+/// Reads every Reaper and Ableton project below `root`. Ableton's automatic backups
+/// are skipped so each set is only counted once.
+pub fn scan_projects(root: &Path) -> Vec<Project> {
+    let mut projects = Vec::new();
+
+    for entry in WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != "Backup")
+        .filter_map(|e| e.ok())
+    {
+        let path = entry.path();
+        let kind = match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase)
+            .as_deref()
+        {
+            Some("rpp") => ProjectKind::Reaper,
+            Some("als") => ProjectKind::Ableton,
+            _ => continue,
+        };
+
+        let plugins = match kind {
+            ProjectKind::Reaper => std::fs::read(path)
+                .map(|data| parse_rpp(&String::from_utf8_lossy(&data)))
+                .map_err(anyhow::Error::from),
+            ProjectKind::Ableton => read_als(path).map(|xml| parse_als(&xml)),
+        };
+
+        match plugins {
+            Ok(plugins) => projects.push(Project {
+                path: path.to_path_buf(),
+                modified: entry.metadata().ok().and_then(|m| m.modified().ok()),
+                plugins,
+            }),
+            Err(e) => eprintln!("Error reading project {:?}: {}", path, e),
+        }
+    }
+
+    projects
+}
+
+fn read_als(path: &Path) -> Result<String> {
+    let mut xml = String::new();
+    GzDecoder::new(std::fs::File::open(path)?).read_to_string(&mut xml)?;
+    Ok(xml)
+}
+
+/// Reaper stores each plugin instance as a chunk header such as
+/// `<VST "VST3: Pro-Q 3 (FabFilter)" "FabFilter Pro-Q 3.vst3" 0 "" 1234{CID...} ""` or
+/// `<AU "AU: AUDelay (Apple)" "Apple: AUDelay" "" ...`.
+pub fn parse_rpp(content: &str) -> Vec<PluginReference> {
+    let mut references = Vec::new();
+
+    for line in content.lines().map(str::trim_start) {
+        let Some(rest) = line
+            .strip_prefix("<VST ")
+            .or_else(|| line.strip_prefix("<AU "))
+        else {
+            continue;
+        };
+        let is_vst = line.starts_with("<VST ");
+
+        let tokens: Vec<&str> = RPP_TOKEN
+            .captures_iter(rest)
+            .filter_map(|c| c.get(1).or_else(|| c.get(2)).map(|m| m.as_str()))
+            .collect();
+        let Some(display) = tokens.first() else {
+            continue;
+        };
+
+        // "VST3i: Name (Vendor)" -> "Name"
+        let name = display.split_once(": ").map_or(*display, |(_, n)| n);
+        let name = match name.rfind(" (") {
+            Some(i) if name.ends_with(')') => &name[..i],
+            _ => name,
+        };
+
+        let file_name = is_vst
+            .then(|| tokens.get(1))
+            .flatten()
+            .filter(|f| !f.is_empty())
+            .map(|f| f.to_string());
+        let id = is_vst
+            .then(|| tokens.get(4))
+            .flatten()
+            .map(|t| t.split('<').next().unwrap_or(t).to_string())
+            .filter(|t| !t.is_empty());
+
+        references.push(PluginReference {
+            name: name.to_string(),
+            file_name,
+            id,
+        });
+    }
+
+    references
+}
+
+/// Ableton keeps each device's plugin in a `VstPluginInfo`, `Vst3PluginInfo` or
+/// `AuPluginInfo` element whose children hold the details as `Value` attributes.
+pub fn parse_als(xml: &str) -> Vec<PluginReference> {
+    ALS_PLUGIN_INFO
+        .find_iter(xml)
+        .filter_map(|info| {
+            let mut name = None;
+            let mut file_name = None;
+            let mut id = None;
+
+            for caps in ALS_VALUE.captures_iter(info.as_str()) {
+                let value = &caps[2];
+                if value.is_empty() {
+                    continue;
+                }
+                match &caps[1] {
+                    "PlugName" | "Name" => {
+                        name.get_or_insert_with(|| value.to_string());
+                    }
+                    "FileName" => file_name = Some(value.to_string()),
+                    "Path" => {
+                        file_name.get_or_insert_with(|| {
+                            value
+                                .rsplit(['/', '\\'])
+                                .next()
+                                .unwrap_or(value)
+                                .to_string()
+                        });
+                    }
+                    "UniqueId" => id = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+
+            Some(PluginReference {
+                name: name?,
+                file_name,
+                id,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::PluginType;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    const RPP: &str = r#"<REAPER_PROJECT 0.1 "7.0/macOS-arm64" 1700000000
+  <TRACK {3C1D2E4F-0000-0000-0000-000000000000}
+    NAME "Lead Vox"
+    <FXCHAIN
+      <VST "VST3: Pro-Q 3 (FabFilter)" "FabFilter Pro-Q 3.vst3" 0 "" 1234567890{72C4DB717A4D459AB97E51745D84B39D} ""
+        ZXE9
+      >
+      <VST "VSTi: Serum (Xfer Records)" Serum.dll 0 "" 1483109208<56535458667350736572756D00000000> ""
+      >
+      <VST "VST: Valhalla Room (x64) (Valhalla DSP, LLC)" ValhallaRoom_x64.dll 0 "" 1919247213
+      >
+      <AU "AU: AUDelay (Apple)" "Apple: AUDelay" "" 1635083896 1684368505 1634758764
+      >
+    >
+  >
+>
+"#;
+
+    const ALS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Ableton MajorVersion="5">
+  <PluginDevice Id="0">
+    <PluginDesc>
+      <VstPluginInfo Id="0">
+        <WinPosX Value="0" />
+        <Path Value="C:/VST/Valhalla/ValhallaRoom_x64.dll" />
+        <PlugName Value="ValhallaRoom_x64" />
+        <UniqueId Value="1919247213" />
+      </VstPluginInfo>
+    </PluginDesc>
+  </PluginDevice>
+  <PluginDevice Id="1">
+    <PluginDesc>
+      <Vst3PluginInfo Id="0">
+        <Name Value="" />
+        <Name Value="Pro-Q 3" />
+      </Vst3PluginInfo>
+    </PluginDesc>
+  </PluginDevice>
+  <AuPluginDevice Id="2">
+    <PluginDesc>
+      <AuPluginInfo Id="0">
+        <Name Value="AUDelay" />
+        <Manufacturer Value="Apple" />
+      </AuPluginInfo>
+    </PluginDesc>
+  </AuPluginDevice>
+</Ableton>
+"#;
+
+    fn reference(name: &str, file_name: Option<&str>, id: Option<&str>) -> PluginReference {
+        PluginReference {
+            name: name.to_string(),
+            file_name: file_name.map(str::to_string),
+            id: id.map(str::to_string),
+        }
+    }
+
+    fn write_als(path: &Path, xml: &str) {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(xml.as_bytes()).unwrap();
+        std::fs::write(path, encoder.finish().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn reads_reaper_chunks() {
+        assert_eq!(
+            parse_rpp(RPP),
+            [
+                reference(
+                    "Pro-Q 3",
+                    Some("FabFilter Pro-Q 3.vst3"),
+                    Some("1234567890{72C4DB717A4D459AB97E51745D84B39D}")
+                ),
+                reference("Serum", Some("Serum.dll"), Some("1483109208")),
+                reference(
+                    "Valhalla Room (x64)",
+                    Some("ValhallaRoom_x64.dll"),
+                    Some("1919247213")
+                ),
+                reference("AUDelay", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_ableton_plugin_info() {
+        assert_eq!(
+            parse_als(ALS),
+            [
+                reference(
+                    "ValhallaRoom_x64",
+                    Some("ValhallaRoom_x64.dll"),
+                    Some("1919247213")
+                ),
+                reference("Pro-Q 3", None, None),
+                reference("AUDelay", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn scans_gzipped_sets_and_skips_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Mix.RPP"), RPP).unwrap();
+        write_als(&dir.path().join("Song.als"), ALS);
+        std::fs::create_dir(dir.path().join("Backup")).unwrap();
+        write_als(&dir.path().join("Backup/Song [2024-01-01].als"), ALS);
+        std::fs::write(dir.path().join("Broken.als"), "not gzip").unwrap();
+
+        let mut projects = scan_projects(dir.path());
+        projects.sort_by_key(|p| p.name());
+        let names: Vec<String> = projects.iter().map(Project::name).collect();
+        assert_eq!(names, ["Mix", "Song"]);
+        assert_eq!(projects[1].plugins.len(), 3);
+    }
+
+    #[test]
+    fn matches_by_id_then_file_name_then_name() {
+        let plugin = |name: &str, path: &str, bundle_id: Option<&str>| Plugin {
+            path: path.into(),
+            bundle_id: bundle_id.map(str::to_string),
+            ..Plugin::test(name, "Vendor", PluginType::VST3)
+        };
+
+        let by_id = reference("Renamed", None, Some("1234{72C4DB71-7A4D}"));
+        assert!(by_id.matches(&plugin("Pro-Q 3", "/a.vst3", Some("72c4db717a4d"))));
+        assert!(!by_id.matches(&plugin("Pro-Q 3", "/a.vst3", Some("72c4"))));
+
+        let by_file = reference("Serum x64", Some("serum.DLL"), None);
+        assert!(by_file.matches(&plugin("Serum", "/VST/Serum.dll", None)));
+        assert!(!by_file.matches(&plugin("Serum", "/VST/Serum2.dll", None)));
+
+        let by_name = reference("Pro-Q 3", Some("Other.vst3"), None);
+        assert!(by_name.matches(&plugin("ProQ3", "/FabFilter Pro-Q 3.vst3", None)));
+        assert!(!reference("", None, None).matches(&plugin("", "/a.vst3", None)));
+    }
+}
@@ -0,0 +1,306 @@
+use super::bom::{parse_bom, BomEntry, BomEntryKind};
+use crate::utils::error::Result;
+use anyhow::Context;
+use plist::Value;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+const BUNDLE_EXTENSIONS: [&str; 9] = [
+    "vst",
+    "vst3",
+    "component",
+    "aaxplugin",
+    "clap",
+    "app",
+    "bundle",
+    "framework",
+    "plugin",
+];
+
+#[derive(Debug, Clone)]
+pub struct Receipt {
+    pub package_id: String,
+    pub version: Option<String>,
+    pub install_prefix: PathBuf,
+    pub plist_path: PathBuf,
+    pub bom_path: PathBuf,
+}
+
+impl Receipt {
+    /// Reads a `<package id>.plist` receipt. Its install prefix is resolved against
+    /// `volume_root`, which is `/` except when reading another disk or fixture files.
+    pub fn load(plist_path: &Path, volume_root: &Path) -> Result<Self> {
+        let plist: Value = plist::from_file(plist_path)
+            .with_context(|| format!("Failed to read receipt {:?}", plist_path))?;
+        let dict = plist
+            .as_dictionary()
+            .context("Receipt root is not a dictionary")?;
+
+        let package_id = dict
+            .get("PackageIdentifier")
+            .and_then(Value::as_string)
+            .context("Receipt has no PackageIdentifier")?
+            .to_string();
+        let version = dict
+            .get("PackageVersion")
+            .and_then(Value::as_string)
+            .map(str::to_string);
+        let prefix = dict
+            .get("InstallPrefixPath")
+            .and_then(Value::as_string)
+            .unwrap_or("/");
+
+        Ok(Self {
+            package_id,
+            version,
+            install_prefix: volume_root.join(prefix.trim_start_matches('/')),
+            bom_path: plist_path.with_extension("bom"),
+            plist_path: plist_path.to_path_buf(),
+        })
+    }
+
+    /// The files that make `pkgutil` report the package as installed.
+    pub fn files(&self) -> [PathBuf; 2] {
+        [self.bom_path.clone(), self.plist_path.clone()]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InstalledPackage {
+    pub receipt: Receipt,
+    /// Every entry from the package's BOM, with absolute paths.
+    pub entries: Vec<BomEntry>,
+}
+
+impl InstalledPackage {
+    pub fn installed_size(&self) -> u64 {
+        self.entries
+            .iter()
+            .filter(|e| e.kind == BomEntryKind::File)
+            .map(|e| e.size)
+            .sum()
+    }
+
+    pub fn owns(&self, path: &Path) -> bool {
+        self.entries.iter().any(|e| e.path == path)
+    }
+
+    /// Each bundle the package laid down as a whole, plus any loose files. Directories
+    /// are left alone, since a package lists shared folders such as `/Library/Audio`
+    /// as well as its own.
+    fn installed_items(&self) -> Vec<PathBuf> {
+        let mut paths = BTreeSet::new();
+        for entry in &self.entries {
+            if entry.kind == BomEntryKind::Directory && !is_bundle(&entry.path) {
+                continue;
+            }
+            let target = entry
+                .path
+                .ancestors()
+                .filter(|a| a.starts_with(&self.receipt.install_prefix))
+                .filter(|a| is_bundle(a))
+                .last()
+                .unwrap_or(entry.path.as_path());
+            paths.insert(target.to_path_buf());
+        }
+        paths.into_iter().collect()
+    }
+}
+
+fn is_bundle(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| BUNDLE_EXTENSIONS.iter().any(|b| ext.eq_ignore_ascii_case(b)))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReceiptIndex {
+    pub packages: Vec<InstalledPackage>,
+}
+
+impl ReceiptIndex {
+    pub fn load() -> Self {
+        let mut index = Self::default();
+        for dir in receipt_dirs() {
+            index
+                .packages
+                .extend(Self::load_from(&dir, Path::new("/")).packages);
+        }
+        index
+    }
+
+    /// Loads every third-party receipt in `receipts_dir`. Apple's own packages are
+    /// skipped: they never install plugins and their BOMs are by far the largest.
+    pub fn load_from(receipts_dir: &Path, volume_root: &Path) -> Self {
+        let mut packages = Vec::new();
+        let Ok(entries) = std::fs::read_dir(receipts_dir) else {
+            return Self { packages };
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("plist"))
+            {
+                continue;
+            }
+
+            let Ok(receipt) = Receipt::load(&path, volume_root) else {
+                continue;
+            };
+            if receipt.package_id.starts_with("com.apple.") {
+                continue;
+            }
+
+            let entries = match std::fs::read(&receipt.bom_path)
+                .map_err(anyhow::Error::from)
+                .and_then(|data| parse_bom(&data))
+            {
+                Ok(entries) => entries,
+                Err(e) => {
+                    eprintln!("Error reading BOM {:?}: {}", receipt.bom_path, e);
+                    continue;
+                }
+            };
+
+            let entries = entries
+                .into_iter()
+                .map(|e| BomEntry {
+                    path: receipt.install_prefix.join(&e.path),
+                    ..e
+                })
+                .collect();
+            packages.push(InstalledPackage { receipt, entries });
+        }
+
+        packages.sort_by(|a, b| a.receipt.package_id.cmp(&b.receipt.package_id));
+        Self { packages }
+    }
+
+    pub fn package_for(&self, path: &Path) -> Option<&InstalledPackage> {
+        self.packages.iter().find(|p| p.owns(path))
+    }
+
+    /// What to move to the bin to uninstall `package`: its bundles and loose files,
+    /// less any that another package also installed something at or inside.
+    pub fn removal_paths(&self, package: &InstalledPackage) -> Vec<PathBuf> {
+        let others: Vec<&InstalledPackage> = self
+            .packages
+            .iter()
+            .filter(|p| p.receipt.package_id != package.receipt.package_id)
+            .collect();
+        package
+            .installed_items()
+            .into_iter()
+            .filter(|item| {
+                !others
+                    .iter()
+                    .any(|other| other.entries.iter().any(|e| e.path.starts_with(item)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn receipt_dirs() -> Vec<PathBuf> {
+    super::macos::get_receipt_dirs()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn receipt_dirs() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::bom::tests::build_bom;
+    use super::*;
+    use plist::Dictionary;
+
+    fn write_receipt(dir: &Path, package_id: &str, entries: &[(&str, BomEntryKind, u32)]) {
+        let mut plist = Dictionary::new();
+        plist.insert("PackageIdentifier".into(), package_id.into());
+        plist.insert("PackageVersion".into(), "1.2.0".into());
+        plist.insert("InstallPrefixPath".into(), "/".into());
+        let plist_path = dir.join(format!("{}.plist", package_id));
+        plist::to_file_xml(&plist_path, &Value::Dictionary(plist)).unwrap();
+        std::fs::write(plist_path.with_extension("bom"), build_bom(entries)).unwrap();
+    }
+
+    const SHARED: &str = "./Library/Application Support/Vendor/shared.dat";
+
+    fn fixture() -> (tempfile::TempDir, ReceiptIndex) {
+        use BomEntryKind::{Directory, File};
+
+        let volume = tempfile::tempdir().unwrap();
+        let receipts = volume.path().join("var/db/receipts");
+        std::fs::create_dir_all(&receipts).unwrap();
+
+        let folders = [
+            (".", Directory, 0),
+            ("./Library", Directory, 0),
+            ("./Library/VST3", Directory, 0),
+            ("./Library/Application Support", Directory, 0),
+            ("./Library/Application Support/Vendor", Directory, 0),
+        ];
+        let mut synth = folders.to_vec();
+        synth.extend([
+            ("./Library/VST3/Synth.vst3", Directory, 0),
+            ("./Library/VST3/Synth.vst3/Synth", File, 1000),
+            (SHARED, File, 10),
+            ("./Library/Application Support/Vendor/synth.dat", File, 20),
+        ]);
+        let mut reverb = folders.to_vec();
+        reverb.extend([
+            ("./Library/VST3/Reverb.vst3", Directory, 0),
+            ("./Library/VST3/Reverb.vst3/Reverb", File, 500),
+            (SHARED, File, 10),
+        ]);
+
+        write_receipt(&receipts, "com.vendor.synth", &synth);
+        write_receipt(&receipts, "com.vendor.reverb", &reverb);
+        write_receipt(&receipts, "com.apple.pkg.Something", &folders);
+
+        let index = ReceiptIndex::load_from(&receipts, volume.path());
+        (volume, index)
+    }
+
+    #[test]
+    fn loads_third_party_receipts_under_the_volume_root() {
+        let (volume, index) = fixture();
+        let ids: Vec<_> = index
+            .packages
+            .iter()
+            .map(|p| p.receipt.package_id.as_str())
+            .collect();
+        assert_eq!(ids, ["com.vendor.reverb", "com.vendor.synth"]);
+
+        let synth = &index.packages[1];
+        assert_eq!(synth.receipt.version.as_deref(), Some("1.2.0"));
+        assert_eq!(synth.receipt.install_prefix, volume.path());
+        assert_eq!(synth.installed_size(), 1030);
+
+        let binary = volume.path().join("Library/VST3/Synth.vst3/Synth");
+        let owner = index.package_for(&binary).unwrap();
+        assert_eq!(owner.receipt.package_id, "com.vendor.synth");
+    }
+
+    #[test]
+    fn removal_leaves_folders_and_shared_files() {
+        let (volume, index) = fixture();
+        let root = volume.path();
+
+        assert_eq!(
+            index.removal_paths(&index.packages[1]),
+            [
+                root.join("Library/Application Support/Vendor/synth.dat"),
+                root.join("Library/VST3/Synth.vst3"),
+            ]
+        );
+        assert_eq!(
+            index.removal_paths(&index.packages[0]),
+            [root.join("Library/VST3/Reverb.vst3")]
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use crate::plugin::{clean_manufacturer_name, normalize_name, Plugin};
+use crate::utils::error::Result;
+use std::path::PathBuf;
+
+/// One program from the Windows "Apps & features" list.
+#[derive(Debug, Clone, Default)]
+pub struct UninstallEntry {
+    pub display_name: String,
+    pub display_version: Option<String>,
+    pub publisher: Option<String>,
+    pub install_location: Option<PathBuf>,
+    pub uninstall_string: Option<String>,
+}
+
+impl UninstallEntry {
+    pub fn has_uninstaller(&self) -> bool {
+        self.uninstall_string
+            .as_deref()
+            .is_some_and(|s| !s.trim().is_empty())
+    }
+}
+
+/// Source of installed-program records, so matching can run against a fixed list
+/// instead of the live registry.
+pub trait UninstallRegistry {
+    fn entries(&self) -> Vec<UninstallEntry>;
+}
+
+/// A registry that only knows about the entries it was built with. It also stands in
+/// for the real registry on platforms that don't have one.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryRegistry {
+    pub entries: Vec<UninstallEntry>,
+}
+
+impl UninstallRegistry for InMemoryRegistry {
+    fn entries(&self) -> Vec<UninstallEntry> {
+        self.entries.clone()
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub struct WindowsRegistry;
+
+#[cfg(target_os = "windows")]
+impl UninstallRegistry for WindowsRegistry {
+    fn entries(&self) -> Vec<UninstallEntry> {
+        super::windows::read_uninstall_entries()
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn system_registry() -> Box<dyn UninstallRegistry> {
+    Box::new(WindowsRegistry)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn system_registry() -> Box<dyn UninstallRegistry> {
+    Box::new(InMemoryRegistry::default())
+}
+
+/// Picks the installed program that most likely put `plugin` on disk.
+///
+/// An install location containing the plugin is the strongest evidence, unless that
+/// location is a shared folder such as `Common Files\VST3` (anything holding one of
+/// `plugin_dirs`). Otherwise the publisher has to match the vendor and the display
+/// name has to mention the plugin.
+pub fn find_uninstaller<'a>(
+    entries: &'a [UninstallEntry],
+    plugin: &Plugin,
+    plugin_dirs: &[PathBuf],
+) -> Option<&'a UninstallEntry> {
+    let vendor = normalize_name(&clean_manufacturer_name(&plugin.manufacturer));
+    let name = normalize_name(&plugin.name);
+
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let owns_location = entry.install_location.as_ref().is_some_and(|location| {
+                !location.as_os_str().is_empty()
+                    && plugin.path.starts_with(location)
+                    && !plugin_dirs.iter().any(|dir| dir.starts_with(location))
+            });
+
+            let publisher_matches = entry.publisher.as_deref().is_some_and(|publisher| {
+                let publisher = normalize_name(&clean_manufacturer_name(publisher));
+                !publisher.is_empty()
+                    && !vendor.is_empty()
+                    && (publisher.contains(&vendor) || vendor.contains(&publisher))
+            });
+
+            let display_name = normalize_name(&entry.display_name);
+            let names_match = !name.is_empty() && display_name.contains(&name);
+
+            let score = if owns_location {
+                3
+            } else if publisher_matches && names_match {
+                2
+            } else {
+                0
+            };
+
+            (score > 0).then_some((score, entry))
+        })
+        .max_by_key(|(score, entry)| (*score, entry.has_uninstaller()))
+        .map(|(_, entry)| entry)
+}
+
+/// Starts the vendor's own uninstaller and returns without waiting for it.
+#[cfg(target_os = "windows")]
+pub fn launch_uninstaller(entry: &UninstallEntry) -> Result<()> {
+    use std::os::windows::process::CommandExt;
+
+    let command = entry
+        .uninstall_string
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| anyhow::anyhow!("{} has no uninstaller", entry.display_name))?;
+
+    std::process::Command::new("cmd")
+        .raw_arg(cmd_arguments(command))
+        .spawn()?;
+    Ok(())
+}
+
+/// The arguments that make `cmd` run `command` untouched. UninstallString is a complete
+/// command line, quoting included, so it's wrapped in one more pair of quotes; with
+/// `/S` cmd strips exactly that pair, whatever parentheses or quotes are inside.
+#[cfg(any(target_os = "windows", test))]
+fn cmd_arguments(command: &str) -> String {
+    format!("/S /C \"{}\"", command)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn launch_uninstaller(entry: &UninstallEntry) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "Cannot run the uninstaller for {} on this platform",
+        entry.display_name
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::PluginType;
+
+    fn entry(name: &str, publisher: &str, location: &str, uninstaller: bool) -> UninstallEntry {
+        UninstallEntry {
+            display_name: name.to_string(),
+            display_version: None,
+            publisher: Some(publisher.to_string()),
+            install_location: Some(PathBuf::from(location)),
+            uninstall_string: uninstaller.then(|| format!("\"{}/uninstall.exe\"", location)),
+        }
+    }
+
+    /// Looks up the uninstaller for FabFilter's Pro-Q 3 at `path` among `entries`.
+    fn find(entries: Vec<UninstallEntry>, path: &str) -> Option<String> {
+        let registry = InMemoryRegistry { entries };
+        let plugin = Plugin {
+            path: path.into(),
+            ..Plugin::test("Pro-Q 3", "FabFilter", PluginType::VST3)
+        };
+        let plugin_dirs = [PathBuf::from("/Common/VST3")];
+        find_uninstaller(&registry.entries(), &plugin, &plugin_dirs).map(|e| e.display_name.clone())
+    }
+
+    #[test]
+    fn commands_keep_their_own_quotes() {
+        let command = r#""C:\Program Files (x86)\Vendor\unins000.exe" /SILENT"#;
+        assert_eq!(
+            cmd_arguments(command),
+            r#"/S /C ""C:\Program Files (x86)\Vendor\unins000.exe" /SILENT""#
+        );
+    }
+
+    #[test]
+    fn install_location_wins() {
+        let entries = vec![
+            entry("FabFilter Pro-Q 3", "FabFilter", "/Apps/Other", true),
+            entry("Total Bundle", "Someone", "/Apps/FabFilter", true),
+        ];
+        assert_eq!(
+            find(entries, "/Apps/FabFilter/VST3/Pro-Q 3.vst3").as_deref(),
+            Some("Total Bundle")
+        );
+    }
+
+    #[test]
+    fn shared_folders_fall_back_to_publisher_and_name() {
+        let entries = vec![
+            entry("Shared Files", "Microsoft", "/Common", true),
+            entry("FabFilter Pro-Q 3 x64", "FabFilter B.V.", "", true),
+            entry("FabFilter Pro-C 2", "FabFilter B.V.", "", true),
+        ];
+        assert_eq!(
+            find(entries, "/Common/VST3/Pro-Q 3.vst3").as_deref(),
+            Some("FabFilter Pro-Q 3 x64")
+        );
+    }
+
+    #[test]
+    fn publisher_and_name_must_both_match() {
+        let entries = vec![
+            entry("FabFilter Pro-C 2", "FabFilter", "", true),
+            entry("Pro-Q 3 Presets", "Someone Else", "", true),
+        ];
+        assert_eq!(find(entries, "/Common/VST3/Pro-Q 3.vst3"), None);
+    }
+
+    #[test]
+    fn prefers_entries_with_an_uninstaller() {
+        let entries = vec![
+            entry("FabFilter Pro-Q 3", "FabFilter", "", true),
+            entry("FabFilter Pro-Q 3 (update)", "FabFilter", "", false),
+        ];
+        assert_eq!(
+            find(entries, "/Common/VST3/Pro-Q 3.vst3").as_deref(),
+            Some("FabFilter Pro-Q 3")
+        );
+    }
+}
@@ -3,6 +3,9 @@ use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
 
+use super::leftovers::{LeftoverKind, LeftoverRoot};
+use super::uninstallers::UninstallEntry;
+
 pub(super) fn get_vst2_paths() -> Vec<PathBuf> {
     let mut paths = HashSet::new();
     if let Some(program_files) = std::env::var_os("ProgramW6432") {
@@ -68,6 +71,71 @@ pub(super) fn get_aax_paths() -> Vec<PathBuf> {
     paths.into_iter().collect()
 }
 
+pub(super) fn get_leftover_roots() -> Vec<LeftoverRoot> {
+    let mut roots = Vec::new();
+    for var in ["APPDATA", "LOCALAPPDATA", "ProgramData"] {
+        if let Some(dir) = std::env::var_os(var) {
+            roots.push(LeftoverRoot::new(dir, LeftoverKind::SupportFiles));
+        }
+    }
+    for var in ["ProgramW6432", "ProgramFiles(x86)"] {
+        if let Some(dir) = std::env::var_os(var) {
+            roots.push(LeftoverRoot::new(
+                PathBuf::from(dir).join("Common Files"),
+                LeftoverKind::SupportFiles,
+            ));
+        }
+    }
+    if let Some(documents) = dirs::document_dir() {
+        roots.push(LeftoverRoot::new(
+            documents.join("VST3 Presets"),
+            LeftoverKind::Presets,
+        ));
+        roots.push(LeftoverRoot::new(documents, LeftoverKind::Documents));
+    }
+    roots
+}
+
+pub(super) fn read_uninstall_entries() -> Vec<UninstallEntry> {
+    let mut entries = Vec::new();
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+
+    for uninstall_path in [
+        "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
+        "SOFTWARE\\WOW6432Node\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
+    ] {
+        let Ok(uninstall_key) = hklm.open_subkey(uninstall_path) else {
+            continue;
+        };
+
+        for program in uninstall_key.enum_keys().filter_map(|k| k.ok()) {
+            let Ok(program_key) = uninstall_key.open_subkey(&program) else {
+                continue;
+            };
+            let Ok(display_name) = program_key.get_value::<String, _>("DisplayName") else {
+                continue;
+            };
+
+            let get_value = |name: &str| {
+                program_key
+                    .get_value::<String, _>(name)
+                    .ok()
+                    .map(|v| v.trim().trim_matches('"').to_string())
+                    .filter(|v| !v.is_empty())
+            };
+
+            entries.push(UninstallEntry {
+                display_name,
+                display_version: get_value("DisplayVersion"),
+                publisher: get_value("Publisher"),
+                install_location: get_value("InstallLocation").map(PathBuf::from),
+                uninstall_string: program_key.get_value::<String, _>("UninstallString").ok(),
+            });
+        }
+    }
+    entries
+}
+
 pub(super) fn is_potential_vst2_file(path: &Path) -> bool {
     if !path
         .extension()
@@ -0,0 +1,415 @@
+use crate::utils::error::Result;
+use anyhow::{anyhow, bail};
+
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_SET: u8 = 0x31;
+const TAG_CONTEXT_0: u8 = 0xa0;
+const TAG_OID: u8 = 0x06;
+const TAG_UTC_TIME: u8 = 0x17;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+
+/// How deeply indefinite-length elements may nest. Real signatures need a handful of
+/// levels; the limit keeps a crafted blob from exhausting the stack.
+const MAX_NESTING: usize = 32;
+
+const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+const OID_ORGANIZATION: &[u8] = &[0x55, 0x04, 0x0a];
+const OID_ORGANIZATIONAL_UNIT: &[u8] = &[0x55, 0x04, 0x0b];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DistinguishedName {
+    pub common_name: Option<String>,
+    pub organization: Option<String>,
+    pub organizational_unit: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Certificate {
+    pub serial: Vec<u8>,
+    pub subject: DistinguishedName,
+    pub issuer: DistinguishedName,
+    /// Validity bounds as `YYYY-MM-DD`, so they compare correctly as strings.
+    pub not_before: Option<String>,
+    pub not_after: Option<String>,
+}
+
+/// Finds the certificate that signed a CMS / PKCS#7 `SignedData` blob, as found in
+/// both Mach-O code signatures and Authenticode. Apple writes these with BER
+/// indefinite lengths, so this is a small BER reader rather than a strict DER one.
+///
+/// The signer is the certificate named by the first `SignerInfo`. Blobs that only
+/// identify it by key identifier fall back to the end of the certificate chain.
+pub fn signed_data_signer(data: &[u8]) -> Result<Option<Certificate>> {
+    let (content_info, _) = read_tlv(data)?;
+    expect_tag(&content_info, TAG_SEQUENCE)?;
+
+    let mut parts = children(content_info.content);
+    let oid = parts.next().ok_or_else(|| anyhow!("Empty ContentInfo"))??;
+    expect_tag(&oid, TAG_OID)?;
+    let explicit = parts
+        .next()
+        .ok_or_else(|| anyhow!("ContentInfo has no content"))??;
+    expect_tag(&explicit, TAG_CONTEXT_0)?;
+
+    let (signed_data, _) = read_tlv(explicit.content)?;
+    expect_tag(&signed_data, TAG_SEQUENCE)?;
+
+    let mut certificates = Vec::new();
+    let mut signer_infos = None;
+    for field in children(signed_data.content) {
+        let field = field?;
+        match field.tag {
+            // certificates [0] IMPLICIT SET OF Certificate
+            TAG_CONTEXT_0 => {
+                for cert in children(field.content) {
+                    let cert = cert?;
+                    if cert.tag == TAG_SEQUENCE {
+                        certificates.push(parse_certificate(cert.content)?);
+                    }
+                }
+            }
+            // digestAlgorithms comes first and signerInfos last; both are SETs.
+            TAG_SET => signer_infos = Some(field.content),
+            _ => {}
+        }
+    }
+
+    let signer_id = signer_infos.and_then(|infos| signer_id(infos).ok().flatten());
+    let signer = signer_id
+        .and_then(|(issuer, serial)| {
+            certificates
+                .iter()
+                .find(|c| c.issuer == issuer && c.serial == serial)
+        })
+        .or_else(|| end_entity(&certificates));
+    Ok(signer.cloned())
+}
+
+/// The issuer and serial number from the first `SignerInfo`, if it uses that form.
+fn signer_id(signer_infos: &[u8]) -> Result<Option<(DistinguishedName, Vec<u8>)>> {
+    let Some(info) = children(signer_infos).next().transpose()? else {
+        return Ok(None);
+    };
+    let mut fields = children(info.content);
+    let _version = fields.next().transpose()?;
+    let Some(sid) = fields.next().transpose()? else {
+        return Ok(None);
+    };
+    if sid.tag != TAG_SEQUENCE {
+        return Ok(None);
+    }
+
+    let mut parts = children(sid.content);
+    let (Some(issuer), Some(serial)) = (parts.next().transpose()?, parts.next().transpose()?)
+    else {
+        return Ok(None);
+    };
+    Ok(Some((parse_name(issuer.content)?, serial.content.to_vec())))
+}
+
+/// The end-entity certificate: the one that did not issue any other in the chain.
+fn end_entity(certificates: &[Certificate]) -> Option<&Certificate> {
+    certificates.iter().find(|candidate| {
+        !certificates
+            .iter()
+            .any(|other| other.issuer == candidate.subject && other.subject != candidate.subject)
+    })
+}
+
+fn parse_certificate(content: &[u8]) -> Result<Certificate> {
+    let (tbs, _) = read_tlv(content)?;
+    expect_tag(&tbs, TAG_SEQUENCE)?;
+
+    let mut fields = children(tbs.content).peekable();
+    if let Some(Ok(first)) = fields.peek() {
+        if first.tag == TAG_CONTEXT_0 {
+            fields.next();
+        }
+    }
+
+    let mut next = || -> Result<Tlv> {
+        fields
+            .next()
+            .ok_or_else(|| anyhow!("Truncated certificate"))?
+    };
+    let serial = next()?.content.to_vec();
+    let _signature_algorithm = next()?;
+    let issuer = parse_name(next()?.content)?;
+    let validity = next()?;
+    let subject = parse_name(next()?.content)?;
+
+    let mut times = children(validity.content);
+    let not_before = times.next().transpose()?.and_then(|t| parse_time(&t));
+    let not_after = times.next().transpose()?.and_then(|t| parse_time(&t));
+
+    Ok(Certificate {
+        serial,
+        subject,
+        issuer,
+        not_before,
+        not_after,
+    })
+}
+
+fn parse_name(content: &[u8]) -> Result<DistinguishedName> {
+    let mut name = DistinguishedName::default();
+    for rdn in children(content) {
+        for attribute in children(rdn?.content) {
+            let mut parts = children(attribute?.content);
+            let (Some(oid), Some(value)) = (parts.next().transpose()?, parts.next().transpose()?)
+            else {
+                continue;
+            };
+            let value = decode_string(&value);
+            match oid.content {
+                OID_COMMON_NAME => name.common_name = value,
+                OID_ORGANIZATION => name.organization = value,
+                OID_ORGANIZATIONAL_UNIT => name.organizational_unit = value,
+                _ => {}
+            }
+        }
+    }
+    Ok(name)
+}
+
+fn decode_string(tlv: &Tlv) -> Option<String> {
+    let value = match tlv.tag {
+        // BMPString
+        0x1e => {
+            let units: Vec<u16> = tlv
+                .content
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(tlv.content).into_owned(),
+    };
+    let value = value.trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+fn parse_time(tlv: &Tlv) -> Option<String> {
+    let text = std::str::from_utf8(tlv.content).ok()?;
+    let (year, rest) = match tlv.tag {
+        TAG_UTC_TIME => {
+            let yy: u32 = text.get(0..2)?.parse().ok()?;
+            (if yy >= 50 { 1900 + yy } else { 2000 + yy }, text.get(2..)?)
+        }
+        TAG_GENERALIZED_TIME => (text.get(0..4)?.parse().ok()?, text.get(4..)?),
+        _ => return None,
+    };
+    Some(format!(
+        "{:04}-{}-{}",
+        year,
+        rest.get(0..2)?,
+        rest.get(2..4)?
+    ))
+}
+
+struct Tlv<'a> {
+    tag: u8,
+    content: &'a [u8],
+}
+
+fn expect_tag(tlv: &Tlv, tag: u8) -> Result<()> {
+    if tlv.tag != tag {
+        bail!("Expected ASN.1 tag {:#04x}, found {:#04x}", tag, tlv.tag);
+    }
+    Ok(())
+}
+
+/// Reads one element, returning it and whatever follows it.
+fn read_tlv(data: &[u8]) -> Result<(Tlv<'_>, &[u8])> {
+    read_nested_tlv(data, 0)
+}
+
+fn read_nested_tlv(data: &[u8], depth: usize) -> Result<(Tlv<'_>, &[u8])> {
+    if depth > MAX_NESTING {
+        bail!("ASN.1 data is nested too deeply");
+    }
+    let tag = *data
+        .first()
+        .ok_or_else(|| anyhow!("Unexpected end of ASN.1 data"))?;
+    if tag & 0x1f == 0x1f {
+        bail!("High-tag-number ASN.1 form is not supported");
+    }
+    let first = *data.get(1).ok_or_else(|| anyhow!("Missing ASN.1 length"))?;
+
+    if first == 0x80 {
+        // Indefinite length: children run until an end-of-contents marker.
+        let body = &data[2..];
+        let mut rest = body;
+        loop {
+            if rest.starts_with(&[0, 0]) {
+                let length = body.len() - rest.len();
+                return Ok((
+                    Tlv {
+                        tag,
+                        content: &body[..length],
+                    },
+                    &rest[2..],
+                ));
+            }
+            let (_, after) = read_nested_tlv(rest, depth + 1)?;
+            rest = after;
+        }
+    }
+
+    let (length, header) = if first & 0x80 == 0 {
+        (first as usize, 2)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count > 4 {
+            bail!("ASN.1 length is too large");
+        }
+        let bytes = data
+            .get(2..2 + count)
+            .ok_or_else(|| anyhow!("Truncated ASN.1 length"))?;
+        (
+            bytes.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize),
+            2 + count,
+        )
+    };
+
+    let content = data
+        .get(header..header + length)
+        .ok_or_else(|| anyhow!("ASN.1 element extends past the end of the data"))?;
+    Ok((Tlv { tag, content }, &data[header + length..]))
+}
+
+fn children(content: &[u8]) -> impl Iterator<Item = Result<Tlv<'_>>> {
+    let mut rest = content;
+    std::iter::from_fn(move || {
+        if rest.is_empty() || rest.starts_with(&[0, 0]) {
+            return None;
+        }
+        match read_tlv(rest) {
+            Ok((tlv, after)) => {
+                rest = after;
+                Some(Ok(tlv))
+            }
+            Err(e) => {
+                rest = &[];
+                Some(Err(e))
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    const TAG_INTEGER: u8 = 0x02;
+    const TAG_UTF8_STRING: u8 = 0x0c;
+    const OID_SIGNED_DATA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x02];
+
+    /// DER-encodes one element.
+    pub(in crate::scanner) fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut out = vec![tag];
+        match content.len() {
+            len @ 0..=0x7f => out.push(len as u8),
+            len @ 0x80..=0xff => out.extend([0x81, len as u8]),
+            len => out.extend([0x82, (len >> 8) as u8, len as u8]),
+        }
+        out.extend(content);
+        out
+    }
+
+    fn name(common_name: &str, organization: Option<&str>, unit: Option<&str>) -> Vec<u8> {
+        let attributes = [
+            (OID_COMMON_NAME, Some(common_name)),
+            (OID_ORGANIZATION, organization),
+            (OID_ORGANIZATIONAL_UNIT, unit),
+        ];
+        let mut rdns = Vec::new();
+        for (oid, value) in attributes {
+            if let Some(value) = value {
+                let pair = [der(TAG_OID, oid), der(TAG_UTF8_STRING, value.as_bytes())].concat();
+                rdns.extend(der(TAG_SET, &der(TAG_SEQUENCE, &pair)));
+            }
+        }
+        der(TAG_SEQUENCE, &rdns)
+    }
+
+    fn certificate(serial: u8, subject: &[u8], issuer: &[u8]) -> Vec<u8> {
+        let validity = [
+            der(TAG_UTC_TIME, b"240101000000Z"),
+            der(TAG_UTC_TIME, b"290630000000Z"),
+        ]
+        .concat();
+        let tbs = [
+            der(TAG_CONTEXT_0, &der(TAG_INTEGER, &[2])),
+            der(TAG_INTEGER, &[serial]),
+            der(TAG_SEQUENCE, &[]),
+            issuer.to_vec(),
+            der(TAG_SEQUENCE, &validity),
+            subject.to_vec(),
+        ]
+        .concat();
+        der(TAG_SEQUENCE, &der(TAG_SEQUENCE, &tbs))
+    }
+
+    /// A PKCS#7 `SignedData` blob carrying a CA certificate and a signing certificate
+    /// for `common_name`, with a `SignerInfo` naming the latter.
+    pub(in crate::scanner) fn signed_data(
+        common_name: &str,
+        organization: Option<&str>,
+        unit: Option<&str>,
+    ) -> Vec<u8> {
+        let ca = name("Test CA", Some("Test CA Inc"), None);
+        let signer = name(common_name, organization, unit);
+        let certificates = [certificate(1, &ca, &ca), certificate(7, &signer, &ca)].concat();
+
+        let signer_id = [ca.clone(), der(TAG_INTEGER, &[7])].concat();
+        let signer_info = [der(TAG_INTEGER, &[1]), der(TAG_SEQUENCE, &signer_id)].concat();
+
+        let signed_data = [
+            der(TAG_INTEGER, &[1]),
+            der(TAG_SET, &[]),
+            der(TAG_SEQUENCE, &[]),
+            der(TAG_CONTEXT_0, &certificates),
+            der(TAG_SET, &der(TAG_SEQUENCE, &signer_info)),
+        ]
+        .concat();
+        let content_info = [
+            der(TAG_OID, OID_SIGNED_DATA),
+            der(TAG_CONTEXT_0, &der(TAG_SEQUENCE, &signed_data)),
+        ]
+        .concat();
+        der(TAG_SEQUENCE, &content_info)
+    }
+
+    #[test]
+    fn finds_the_certificate_the_signer_info_names() {
+        let blob = signed_data("Synth Signing", Some("Vendor Ltd"), Some("ABCDE12345"));
+        let signer = signed_data_signer(&blob).unwrap().unwrap();
+        assert_eq!(signer.serial, [7]);
+        assert_eq!(signer.subject.common_name.as_deref(), Some("Synth Signing"));
+        assert_eq!(signer.subject.organization.as_deref(), Some("Vendor Ltd"));
+        assert_eq!(
+            signer.subject.organizational_unit.as_deref(),
+            Some("ABCDE12345")
+        );
+        assert_eq!(signer.issuer.common_name.as_deref(), Some("Test CA"));
+        assert_eq!(signer.not_before.as_deref(), Some("2024-01-01"));
+        assert_eq!(signer.not_after.as_deref(), Some("2029-06-30"));
+    }
+
+    #[test]
+    fn deeply_nested_indefinite_lengths_are_rejected() {
+        let data = [TAG_SEQUENCE, 0x80].repeat(100_000);
+        assert!(read_tlv(&data).is_err());
+    }
+
+    #[test]
+    fn indefinite_lengths_within_the_limit_are_read() {
+        let mut data = [TAG_SEQUENCE, 0x80].repeat(MAX_NESTING);
+        data.extend([TAG_OID, 1, 0x2a]);
+        data.extend([0, 0].repeat(MAX_NESTING));
+        let (tlv, rest) = read_tlv(&data).unwrap();
+        assert_eq!(tlv.tag, TAG_SEQUENCE);
+        assert!(rest.is_empty());
+    }
+}
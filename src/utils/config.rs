@@ -0,0 +1,48 @@
+use crate::utils::error::Result;
+use anyhow::Context;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Where user settings and overrides live, e.g. `~/Library/Application Support/plugin-manager`
+/// on macOS or `%APPDATA%\plugin-manager` on Windows.
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("plugin-manager"))
+}
+
+pub fn config_file(name: &str) -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(name))
+}
+
+/// Reads a JSON settings file, falling back to the default when it is missing or
+/// unreadable so a bad file never stops the app from starting.
+pub fn load_json<T: DeserializeOwned + Default>(name: &str) -> T {
+    try_load_json(name).unwrap_or_else(|e| {
+        eprintln!("{:#}", e);
+        T::default()
+    })
+}
+
+/// Like `load_json`, but a file that exists and can't be read or parsed is an error,
+/// for settings where quietly starting over would be unsafe.
+pub fn try_load_json<T: DeserializeOwned + Default>(name: &str) -> Result<T> {
+    let Some(path) = config_file(name) else {
+        return Ok(T::default());
+    };
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let content =
+        std::fs::read_to_string(&path).with_context(|| format!("Error reading {:?}", path))?;
+    serde_json::from_str(&content).with_context(|| format!("Error reading {:?}", path))
+}
+
+pub fn save_json<T: Serialize>(name: &str, value: &T) -> Result<()> {
+    let path = config_file(name).context("No configuration directory on this system")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(value)?;
+    std::fs::write(&path, content).with_context(|| format!("Failed to write {:?}", path))
+}
@@ -0,0 +1,664 @@
+use crate::utils::error::Result;
+use crate::utils::fs::{TrashOutcome, TrashResult};
+use anyhow::{bail, Context};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// The hidden command-line subcommand the elevated copy of this program runs.
+pub const HELPER_COMMAND: &str = "elevated-trash";
+
+/// Each item the helper moves, as its original path and where it went.
+type Moves = Vec<(PathBuf, PathBuf)>;
+
+/// A way to run a program with administrator rights. Only the helper subcommand is
+/// ever run this way, so the GUI itself never runs privileged.
+pub trait Elevator {
+    fn name(&self) -> &'static str;
+    /// Runs `program` with `args` as an administrator and waits for it to exit. Fails
+    /// if the user cancels the prompt or the program exits unsuccessfully.
+    fn run(&self, program: &Path, args: &[OsString]) -> Result<()>;
+}
+
+/// Moves `paths` to the user's bin through `elevator`. Each path is reported as moved
+/// if it's gone afterwards, whatever the helper said.
+pub fn elevated_trash(elevator: &dyn Elevator, paths: &[PathBuf]) -> Vec<TrashResult> {
+    trash_into(elevator, paths, user_bin().as_deref())
+}
+
+fn trash_into(elevator: &dyn Elevator, paths: &[PathBuf], bin: Option<&Path>) -> Vec<TrashResult> {
+    let exists = |path: &Path| std::fs::symlink_metadata(path).is_ok();
+    let existing: Vec<PathBuf> = paths.iter().filter(|p| exists(p)).cloned().collect();
+
+    let mut moves = Vec::new();
+    let run = std::env::current_exe()
+        .context("Could not find this program to run it as an administrator")
+        .and_then(|program| {
+            let args;
+            (args, moves) = helper_args(&existing, bin)?;
+            elevator.run(&program, &args)
+        });
+    record_moves(bin, &moves);
+
+    paths
+        .iter()
+        .map(|path| {
+            let outcome = if !existing.contains(path) {
+                TrashOutcome::NotFound
+            } else if !exists(path) {
+                TrashOutcome::Moved
+            } else {
+                match &run {
+                    Ok(()) => TrashOutcome::Failed("Still in place after moving".to_string()),
+                    Err(e) => TrashOutcome::Failed(format!("{:#}", e)),
+                }
+            };
+            TrashResult {
+                path: path.clone(),
+                outcome,
+            }
+        })
+        .collect()
+}
+
+/// Moves each item from the bin back to its original path through `elevator`, for
+/// items that came out of a folder the user can't write to. Each goes back owned by
+/// whoever owns the folder it returns to.
+#[cfg(unix)]
+pub fn elevated_restore(elevator: &dyn Elevator, items: &[(PathBuf, PathBuf)]) -> Result<()> {
+    use std::collections::BTreeMap;
+    use std::os::unix::fs::MetadataExt;
+
+    let program = std::env::current_exe()
+        .context("Could not find this program to run it as an administrator")?;
+    let mut by_owner: BTreeMap<(u32, u32), Vec<OsString>> = BTreeMap::new();
+    for (trashed, original) in items {
+        let folder = original
+            .parent()
+            .context("Can't put back a path without a folder")?;
+        let owner = std::fs::metadata(folder)
+            .with_context(|| format!("{} no longer exists", folder.display()))?;
+        let pairs = by_owner.entry((owner.uid(), owner.gid())).or_default();
+        pairs.push(trashed.clone().into_os_string());
+        pairs.push(original.clone().into_os_string());
+    }
+
+    for ((uid, gid), pairs) in by_owner {
+        let mut args = vec![
+            OsString::from(HELPER_COMMAND),
+            OsString::from("--owner"),
+            OsString::from(format!("{}:{}", uid, gid)),
+        ];
+        args.extend(pairs);
+        elevator.run(&program, &args)?;
+    }
+    Ok(())
+}
+
+/// The Recycle Bin puts items back itself, so there's nothing to elevate.
+#[cfg(target_os = "windows")]
+pub fn elevated_restore(_elevator: &dyn Elevator, _items: &[(PathBuf, PathBuf)]) -> Result<()> {
+    bail!("Items can only be put back from the Recycle Bin")
+}
+
+/// On Unix the helper renames each item into the user's bin and hands it to the user,
+/// so it can be emptied or put back like anything else binned there.
+#[cfg(unix)]
+fn helper_args(paths: &[PathBuf], bin: Option<&Path>) -> Result<(Vec<OsString>, Moves)> {
+    use std::os::unix::fs::MetadataExt;
+
+    let bin = bin.context("No bin folder to move items into")?;
+    std::fs::create_dir_all(bin)?;
+    let home = dirs::home_dir().context("No home folder")?;
+    let owner = std::fs::metadata(home)?;
+
+    let mut args = vec![
+        OsString::from(HELPER_COMMAND),
+        OsString::from("--owner"),
+        OsString::from(format!("{}:{}", owner.uid(), owner.gid())),
+    ];
+    let mut moves: Moves = Vec::new();
+    for path in paths {
+        let destination = unique_destination(bin, path, &moves)?;
+        args.push(path.clone().into_os_string());
+        args.push(destination.clone().into_os_string());
+        moves.push((path.clone(), destination));
+    }
+    Ok((args, moves))
+}
+
+/// The helper recycles items itself, so they land in the bin of whoever approved the
+/// UAC prompt. That's the same user unless a standard user typed in an administrator's
+/// password, in which case they go to that administrator's Recycle Bin.
+#[cfg(target_os = "windows")]
+fn helper_args(paths: &[PathBuf], _bin: Option<&Path>) -> Result<(Vec<OsString>, Moves)> {
+    let mut args = vec![OsString::from(HELPER_COMMAND)];
+    args.extend(paths.iter().map(|p| p.clone().into_os_string()));
+    Ok((args, Vec::new()))
+}
+
+#[cfg(unix)]
+fn unique_destination(bin: &Path, path: &Path, taken: &Moves) -> Result<PathBuf> {
+    let stem = path
+        .file_stem()
+        .context("Can't move a path without a name")?
+        .to_string_lossy();
+    let extension = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+
+    let mut destination = bin.join(format!("{}{}", stem, extension));
+    let mut n = 2;
+    while std::fs::symlink_metadata(&destination).is_ok()
+        || taken.iter().any(|(_, d)| d == &destination)
+    {
+        destination = bin.join(format!("{} {}{}", stem, n, extension));
+        n += 1;
+    }
+    Ok(destination)
+}
+
+#[cfg(target_os = "macos")]
+fn user_bin() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".Trash"))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn user_bin() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("Trash").join("files"))
+}
+
+#[cfg(target_os = "windows")]
+fn user_bin() -> Option<PathBuf> {
+    None
+}
+
+/// The freedesktop bin keeps each item's original path in a `.trashinfo` file, which
+/// the helper leaves to the user's own process to write.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn record_moves(bin: Option<&Path>, moves: &Moves) {
+    use std::os::unix::ffi::OsStrExt;
+
+    let Some(info_dir) = bin.map(|bin| bin.with_file_name("info")) else {
+        return;
+    };
+    let date = deletion_date(std::time::SystemTime::now());
+    for (original, destination) in moves {
+        if std::fs::symlink_metadata(destination).is_err() {
+            continue;
+        }
+        let Some(name) = destination.file_name() else {
+            continue;
+        };
+        let encoded: String = original
+            .as_os_str()
+            .as_bytes()
+            .iter()
+            .map(|&b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{:02X}", b),
+            })
+            .collect();
+        let info = format!("[Trash Info]\nPath={}\nDeletionDate={}\n", encoded, date);
+        let mut file_name = name.to_os_string();
+        file_name.push(".trashinfo");
+        let result = std::fs::create_dir_all(&info_dir)
+            .and_then(|()| std::fs::write(info_dir.join(file_name), info));
+        if let Err(e) = result {
+            eprintln!("Error writing bin record for {:?}: {}", original, e);
+        }
+    }
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn record_moves(_bin: Option<&Path>, _moves: &Moves) {}
+
+/// `YYYY-MM-DDThh:mm:ss` in UTC, which is close enough for sorting the bin.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn deletion_date(time: std::time::SystemTime) -> String {
+    let seconds = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    let (days, rest) = (seconds.div_euclid(86400), seconds.rem_euclid(86400));
+
+    // Howard Hinnant's days-to-civil conversion.
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    )
+}
+
+/// What the elevated helper does: `--owner UID:GID` followed by pairs of source and
+/// destination paths.
+#[cfg(unix)]
+pub fn run_helper(args: &[String]) -> Result<()> {
+    use std::os::unix::fs::lchown;
+
+    let (Some("--owner"), Some(owner)) = (args.first().map(String::as_str), args.get(1)) else {
+        bail!("The helper needs --owner UID:GID");
+    };
+    let (uid, gid) = owner
+        .split_once(':')
+        .and_then(|(uid, gid)| Some((uid.parse().ok()?, gid.parse().ok()?)))
+        .context("Malformed --owner")?;
+    let pairs = &args[2..];
+    if !pairs.len().is_multiple_of(2) {
+        bail!("The helper needs pairs of source and destination paths");
+    }
+
+    let mut failures = Vec::new();
+    for pair in pairs.chunks(2) {
+        let (source, destination) = (Path::new(&pair[0]), Path::new(&pair[1]));
+        let moved = move_item(source, destination).and_then(|()| {
+            walkdir::WalkDir::new(destination)
+                .follow_links(false)
+                .follow_root_links(false)
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .try_for_each(|entry| lchown(entry.path(), Some(uid), Some(gid)))
+        });
+        if let Err(e) = moved {
+            failures.push(format!("{}: {}", source.display(), e));
+        }
+    }
+
+    if !failures.is_empty() {
+        bail!("{}", failures.join("\n"));
+    }
+    Ok(())
+}
+
+/// Renames `source` to `destination`, or copies it and deletes the original when the
+/// bin is on another filesystem than the plugin folder.
+#[cfg(unix)]
+fn move_item(source: &Path, destination: &Path) -> std::io::Result<()> {
+    match std::fs::rename(source, destination) {
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            if let Err(e) = copy_tree(source, destination) {
+                let _ = remove_item(destination);
+                return Err(e);
+            }
+            remove_item(source)
+        }
+        result => result,
+    }
+}
+
+/// Copies a file, symlink or folder tree, leaving symlinks as links.
+#[cfg(unix)]
+fn copy_tree(source: &Path, destination: &Path) -> std::io::Result<()> {
+    let entries = walkdir::WalkDir::new(source)
+        .follow_links(false)
+        .follow_root_links(false);
+    for entry in entries {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(source).unwrap_or(Path::new(""));
+        let target = if relative.as_os_str().is_empty() {
+            destination.to_path_buf()
+        } else {
+            destination.join(relative)
+        };
+
+        let file_type = entry.file_type();
+        if file_type.is_symlink() {
+            std::os::unix::fs::symlink(std::fs::read_link(entry.path())?, &target)?;
+        } else if file_type.is_dir() {
+            std::fs::create_dir(&target)?;
+            std::fs::set_permissions(&target, entry.metadata()?.permissions())?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn remove_item(path: &Path) -> std::io::Result<()> {
+    if std::fs::symlink_metadata(path)?.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+/// What the elevated helper does: moves every path to the Recycle Bin.
+#[cfg(target_os = "windows")]
+pub fn run_helper(args: &[String]) -> Result<()> {
+    let paths: Vec<PathBuf> = args.iter().map(PathBuf::from).collect();
+    let failures: Vec<String> = crate::utils::fs::move_to_trash(&paths)
+        .into_iter()
+        .filter(|r| r.outcome != TrashOutcome::Moved)
+        .map(|r| format!("{}: {}", r.path.display(), r.outcome.label()))
+        .collect();
+
+    if !failures.is_empty() {
+        bail!("{}", failures.join("\n"));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+pub fn system_elevator() -> Option<Box<dyn Elevator>> {
+    Some(Box::new(AuthorizationPrompt))
+}
+
+#[cfg(target_os = "windows")]
+pub fn system_elevator() -> Option<Box<dyn Elevator>> {
+    Some(Box::new(Uac))
+}
+
+/// Prefers pkexec, which shows a graphical prompt; sudo only works here with an askpass
+/// program or cached credentials, since there's no terminal to type into.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn system_elevator() -> Option<Box<dyn Elevator>> {
+    if find_in_path("pkexec") {
+        Some(Box::new(Pkexec))
+    } else if find_in_path("sudo") {
+        Some(Box::new(Sudo))
+    } else {
+        None
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn find_in_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
+
+#[cfg(unix)]
+fn check_output(output: std::io::Result<std::process::Output>, tool: &str) -> Result<()> {
+    let output = output.with_context(|| format!("Could not run {}", tool))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("{} failed: {}", tool, stderr.trim());
+    }
+    Ok(())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+pub struct Pkexec;
+
+#[cfg(all(unix, not(target_os = "macos")))]
+impl Elevator for Pkexec {
+    fn name(&self) -> &'static str {
+        "pkexec"
+    }
+
+    fn run(&self, program: &Path, args: &[OsString]) -> Result<()> {
+        let output = std::process::Command::new("pkexec")
+            .arg(program)
+            .args(args)
+            .output();
+        check_output(output, "pkexec")
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+pub struct Sudo;
+
+#[cfg(all(unix, not(target_os = "macos")))]
+impl Elevator for Sudo {
+    fn name(&self) -> &'static str {
+        "sudo"
+    }
+
+    fn run(&self, program: &Path, args: &[OsString]) -> Result<()> {
+        let mode = if std::env::var_os("SUDO_ASKPASS").is_some() {
+            "-A"
+        } else {
+            "-n"
+        };
+        let output = std::process::Command::new("sudo")
+            .arg(mode)
+            .arg("--")
+            .arg(program)
+            .args(args)
+            .output();
+        check_output(output, "sudo")
+    }
+}
+
+/// The standard macOS administrator prompt, shown by running the helper through
+/// AppleScript's `do shell script ... with administrator privileges`.
+#[cfg(target_os = "macos")]
+pub struct AuthorizationPrompt;
+
+#[cfg(target_os = "macos")]
+impl Elevator for AuthorizationPrompt {
+    fn name(&self) -> &'static str {
+        "the macOS administrator prompt"
+    }
+
+    fn run(&self, program: &Path, args: &[OsString]) -> Result<()> {
+        let command: Vec<String> = std::iter::once(program.as_os_str())
+            .chain(args.iter().map(OsString::as_os_str))
+            .map(|arg| format!("'{}'", arg.to_string_lossy().replace('\'', r"'\''")))
+            .collect();
+        let script = format!(
+            "do shell script \"{}\" with administrator privileges",
+            command.join(" ").replace('\\', r"\\").replace('"', "\\\"")
+        );
+        let output = std::process::Command::new("osascript")
+            .arg("-e")
+            .arg(script)
+            .output();
+        check_output(output, "The administrator prompt")
+    }
+}
+
+/// Runs the helper with the `runas` verb, which shows the UAC prompt.
+#[cfg(target_os = "windows")]
+pub struct Uac;
+
+#[cfg(target_os = "windows")]
+impl Elevator for Uac {
+    fn name(&self) -> &'static str {
+        "User Account Control"
+    }
+
+    fn run(&self, program: &Path, args: &[OsString]) -> Result<()> {
+        use std::ffi::OsStr;
+        use std::os::windows::ffi::OsStrExt;
+        use winapi::um::handleapi::CloseHandle;
+        use winapi::um::processthreadsapi::GetExitCodeProcess;
+        use winapi::um::shellapi::{ShellExecuteExW, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW};
+        use winapi::um::synchapi::WaitForSingleObject;
+        use winapi::um::winbase::INFINITE;
+        use winapi::um::winuser::SW_HIDE;
+
+        fn wide(s: &OsStr) -> Vec<u16> {
+            s.encode_wide().chain(Some(0)).collect()
+        }
+
+        let parameters: Vec<String> = args
+            .iter()
+            .map(|arg| quote_windows_arg(&arg.to_string_lossy()))
+            .collect();
+        let verb = wide(OsStr::new("runas"));
+        let file = wide(program.as_os_str());
+        let parameters = wide(OsStr::new(&parameters.join(" ")));
+
+        let exit_code = unsafe {
+            let mut info: SHELLEXECUTEINFOW = std::mem::zeroed();
+            info.cbSize = std::mem::size_of::<SHELLEXECUTEINFOW>() as u32;
+            info.fMask = SEE_MASK_NOCLOSEPROCESS;
+            info.lpVerb = verb.as_ptr();
+            info.lpFile = file.as_ptr();
+            info.lpParameters = parameters.as_ptr();
+            info.nShow = SW_HIDE;
+
+            if ShellExecuteExW(&mut info) == 0 {
+                return Err(std::io::Error::last_os_error())
+                    .context("The administrator prompt was cancelled or failed");
+            }
+            WaitForSingleObject(info.hProcess, INFINITE);
+            let mut exit_code = 0;
+            GetExitCodeProcess(info.hProcess, &mut exit_code);
+            CloseHandle(info.hProcess);
+            exit_code
+        };
+
+        if exit_code != 0 {
+            bail!("The elevated helper could not move every item");
+        }
+        Ok(())
+    }
+}
+
+/// Quotes an argument the way `CommandLineToArgvW` splits it back apart.
+#[cfg(target_os = "windows")]
+fn quote_windows_arg(arg: &str) -> String {
+    let mut quoted = String::from('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// Runs the helper in this process instead of prompting for a password.
+    struct InProcess;
+
+    impl Elevator for InProcess {
+        fn name(&self) -> &'static str {
+            "in process"
+        }
+
+        fn run(&self, _program: &Path, args: &[OsString]) -> Result<()> {
+            let args: Vec<String> = args
+                .iter()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect();
+            assert_eq!(args[0], HELPER_COMMAND);
+            run_helper(&args[1..])
+        }
+    }
+
+    struct Cancelled;
+
+    impl Elevator for Cancelled {
+        fn name(&self) -> &'static str {
+            "cancelled"
+        }
+
+        fn run(&self, _program: &Path, _args: &[OsString]) -> Result<()> {
+            bail!("The user cancelled the prompt")
+        }
+    }
+
+    fn outcomes(results: Vec<TrashResult>) -> Vec<TrashOutcome> {
+        results.into_iter().map(|r| r.outcome).collect()
+    }
+
+    #[test]
+    fn moves_items_into_the_bin() {
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("Trash").join("files");
+        let plugin = dir.path().join("Synth.vst3");
+        std::fs::create_dir_all(plugin.join("Contents")).unwrap();
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::write(bin.join("Synth.vst3"), "binned earlier").unwrap();
+
+        let paths = [plugin.clone(), dir.path().join("Missing.vst3")];
+        let results = trash_into(&InProcess, &paths, Some(&bin));
+        assert_eq!(
+            outcomes(results),
+            [TrashOutcome::Moved, TrashOutcome::NotFound]
+        );
+        assert!(!plugin.exists());
+        assert!(bin.join("Synth 2.vst3").join("Contents").is_dir());
+
+        #[cfg(not(target_os = "macos"))]
+        {
+            let info = dir.path().join("Trash/info/Synth 2.vst3.trashinfo");
+            let info = std::fs::read_to_string(info).unwrap();
+            assert!(info.contains(&format!("Path={}\n", plugin.display())));
+        }
+    }
+
+    #[test]
+    fn restores_items_to_their_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let trashed = dir.path().join("bin").join("com.vendor.synth.bom");
+        let original = dir.path().join("receipts").join("com.vendor.synth.bom");
+        std::fs::create_dir_all(trashed.parent().unwrap()).unwrap();
+        std::fs::create_dir_all(original.parent().unwrap()).unwrap();
+        std::fs::write(&trashed, "receipt").unwrap();
+
+        elevated_restore(&InProcess, &[(trashed.clone(), original.clone())]).unwrap();
+        assert!(!trashed.exists());
+        assert_eq!(std::fs::read_to_string(&original).unwrap(), "receipt");
+    }
+
+    #[test]
+    fn cancelling_leaves_items_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("bin");
+        let plugin = dir.path().join("Synth.clap");
+        std::fs::write(&plugin, "").unwrap();
+
+        let results = trash_into(&Cancelled, std::slice::from_ref(&plugin), Some(&bin));
+        assert_eq!(
+            outcomes(results),
+            [TrashOutcome::Failed(
+                "The user cancelled the prompt".to_string()
+            )]
+        );
+        assert!(plugin.exists());
+    }
+
+    #[test]
+    fn copies_trees_with_their_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("Synth.component");
+        std::fs::create_dir_all(source.join("Contents/MacOS")).unwrap();
+        std::fs::write(source.join("Contents/MacOS/Synth"), "binary").unwrap();
+        std::os::unix::fs::symlink("MacOS/Synth", source.join("Contents/Current")).unwrap();
+
+        let destination = dir.path().join("Copy.component");
+        copy_tree(&source, &destination).unwrap();
+        remove_item(&source).unwrap();
+
+        assert!(!source.exists());
+        let link = destination.join("Contents/Current");
+        assert_eq!(std::fs::read_link(&link).unwrap(), Path::new("MacOS/Synth"));
+        assert_eq!(std::fs::read_to_string(link).unwrap(), "binary");
+    }
+}
@@ -0,0 +1,424 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+#[cfg(any(target_os = "macos", all(test, unix)))]
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+
+/// Total size in bytes of a file, or of every file beneath a directory.
+/// Symlinks are not followed, so aliased folders are not counted twice.
+pub fn disk_usage(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrashOutcome {
+    Moved,
+    PermissionDenied,
+    InUse,
+    NotFound,
+    /// Refused because the item is on the protect list.
+    Protected,
+    Failed(String),
+}
+
+impl TrashOutcome {
+    pub fn label(&self) -> String {
+        match self {
+            TrashOutcome::Moved => "Moved to bin".to_string(),
+            TrashOutcome::PermissionDenied => "Permission denied".to_string(),
+            TrashOutcome::InUse => "In use by another program".to_string(),
+            TrashOutcome::NotFound => "Not found".to_string(),
+            TrashOutcome::Protected => "Protected; unlock it to remove it".to_string(),
+            TrashOutcome::Failed(reason) => reason.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrashResult {
+    pub path: PathBuf,
+    pub outcome: TrashOutcome,
+}
+
+/// Moves each path to the bin and reports what happened to it. Everything is tried in
+/// one batch first, then anything still in place is retried on its own so each
+/// failure gets its own reason.
+pub fn move_to_trash(paths: &[PathBuf]) -> Vec<TrashResult> {
+    let exists = |path: &Path| std::fs::symlink_metadata(path).is_ok();
+    let existed: Vec<bool> = paths.iter().map(|p| exists(p)).collect();
+    let _ = trash::delete_all(paths.iter().filter(|p| exists(p)));
+
+    paths
+        .iter()
+        .zip(existed)
+        .map(|(path, existed)| {
+            let outcome = if !existed {
+                TrashOutcome::NotFound
+            } else if !exists(path) {
+                TrashOutcome::Moved
+            } else {
+                match trash::delete(path) {
+                    Ok(()) if !exists(path) => TrashOutcome::Moved,
+                    Ok(()) => TrashOutcome::Failed("Still in place after moving".to_string()),
+                    Err(e) => classify_trash_error(path, e),
+                }
+            };
+            TrashResult {
+                path: path.clone(),
+                outcome,
+            }
+        })
+        .collect()
+}
+
+fn classify_trash_error(path: &Path, error: trash::Error) -> TrashOutcome {
+    let code = match &error {
+        trash::Error::Os { code, .. } => Some(*code),
+        #[cfg(all(unix, not(target_os = "macos")))]
+        trash::Error::FileSystem { source, .. } => match source.kind() {
+            std::io::ErrorKind::PermissionDenied => return TrashOutcome::PermissionDenied,
+            std::io::ErrorKind::NotFound => return TrashOutcome::NotFound,
+            _ => source.raw_os_error(),
+        },
+        trash::Error::CouldNotAccess { .. } if !path.exists() => {
+            return TrashOutcome::NotFound;
+        }
+        trash::Error::CouldNotAccess { .. } => return TrashOutcome::PermissionDenied,
+        _ => None,
+    };
+
+    code.and_then(outcome_for_os_code)
+        .unwrap_or_else(|| TrashOutcome::Failed(error.to_string()))
+}
+
+/// Windows reports Win32 errors wrapped in an HRESULT.
+#[cfg(target_os = "windows")]
+fn outcome_for_os_code(code: i32) -> Option<TrashOutcome> {
+    let code = if (code as u32) & 0xffff_0000 == 0x8007_0000 {
+        code & 0xffff
+    } else {
+        code
+    };
+    match code {
+        2 | 3 => Some(TrashOutcome::NotFound),
+        5 => Some(TrashOutcome::PermissionDenied),
+        32 | 33 => Some(TrashOutcome::InUse),
+        _ => None,
+    }
+}
+
+/// Codes from the Foundation error domain, Apple events (when the Finder does the
+/// move) and plain errno values.
+#[cfg(target_os = "macos")]
+fn outcome_for_os_code(code: i32) -> Option<TrashOutcome> {
+    match code {
+        4 | 2 | -43 => Some(TrashOutcome::NotFound),
+        513 | 1 | 13 | -54 | -5000 | -10004 => Some(TrashOutcome::PermissionDenied),
+        16 | -47 => Some(TrashOutcome::InUse),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn outcome_for_os_code(code: i32) -> Option<TrashOutcome> {
+    match code {
+        2 => Some(TrashOutcome::NotFound),
+        1 | 13 | 30 => Some(TrashOutcome::PermissionDenied),
+        16 | 26 => Some(TrashOutcome::InUse),
+        _ => None,
+    }
+}
+
+/// Puts items that were moved to the bin back where they came from, returning each
+/// path that couldn't be put back with the reason. Items are given as their original
+/// path and, where [`locate_in_trash`] found it, their place in the bin. When an item
+/// was binned more than once, the most recent copy is restored.
+#[cfg(not(target_os = "macos"))]
+pub fn restore_from_trash(items: &[(PathBuf, Option<PathBuf>)]) -> Vec<(PathBuf, String)> {
+    use std::collections::HashMap;
+
+    let paths: Vec<&PathBuf> = items.iter().map(|(path, _)| path).collect();
+
+    let items = match trash::os_limited::list() {
+        Ok(items) => items,
+        Err(e) => {
+            return paths
+                .iter()
+                .map(|p| (p.to_path_buf(), e.to_string()))
+                .collect()
+        }
+    };
+    let mut latest: HashMap<PathBuf, trash::TrashItem> = HashMap::new();
+    for item in items {
+        let original = item.original_path();
+        if paths.contains(&&original)
+            && latest
+                .get(&original)
+                .is_none_or(|newest| newest.time_deleted < item.time_deleted)
+        {
+            latest.insert(original, item);
+        }
+    }
+
+    paths
+        .iter()
+        .filter_map(|path| {
+            let Some(item) = latest.remove(*path) else {
+                return Some((path.to_path_buf(), "It is no longer in the bin".to_string()));
+            };
+            trash::os_limited::restore_all([item])
+                .err()
+                .map(|e| (path.to_path_buf(), e.to_string()))
+        })
+        .collect()
+}
+
+/// The Finder keeps its "Put Back" records to itself, so only items whose place in the
+/// bin was found when they were binned can be moved back. Anything else has to be put
+/// back from the Finder.
+#[cfg(target_os = "macos")]
+pub fn restore_from_trash(items: &[(PathBuf, Option<PathBuf>)]) -> Vec<(PathBuf, String)> {
+    items
+        .iter()
+        .filter_map(|(path, trashed)| {
+            let exists = |p: &Path| std::fs::symlink_metadata(p).is_ok();
+            let result = match trashed {
+                Some(trashed) if exists(trashed) && !exists(path) => {
+                    std::fs::rename(trashed, path).map_err(|e| e.to_string())
+                }
+                _ => Err("It isn't in the bin; use Put Back in the Finder".to_string()),
+            };
+            result.err().map(|e| (path.clone(), e))
+        })
+        .collect()
+}
+
+/// Where each of `originals` went in the user's bin, for items binned since `since`.
+/// The Finder renames items that clash with something already there, so the newest
+/// matching entries are picked by when they were moved rather than by name alone.
+#[cfg(target_os = "macos")]
+pub fn locate_in_trash(originals: &[PathBuf], since: SystemTime) -> Vec<Option<PathBuf>> {
+    match dirs::home_dir() {
+        Some(home) => locate_in(&home.join(".Trash"), originals, since),
+        None => vec![None; originals.len()],
+    }
+}
+
+/// Other bins record where each item came from themselves.
+#[cfg(not(target_os = "macos"))]
+pub fn locate_in_trash(originals: &[PathBuf], _since: SystemTime) -> Vec<Option<PathBuf>> {
+    vec![None; originals.len()]
+}
+
+/// Matches `originals`, in the order they were binned, to the entries in `bin` that
+/// changed since `since`, oldest first. Moving an item updates its change time.
+#[cfg(any(target_os = "macos", all(test, unix)))]
+fn locate_in(bin: &Path, originals: &[PathBuf], since: SystemTime) -> Vec<Option<PathBuf>> {
+    use std::os::unix::fs::MetadataExt;
+
+    let since = since
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    let mut moved: Vec<(i64, i64, PathBuf)> = std::fs::read_dir(bin)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let metadata = entry.metadata().ok()?;
+            (metadata.ctime() >= since)
+                .then(|| (metadata.ctime(), metadata.ctime_nsec(), entry.path()))
+        })
+        .collect();
+    moved.sort();
+
+    originals
+        .iter()
+        .map(|original| {
+            let index = moved
+                .iter()
+                .position(|(_, _, path)| is_binned_name(original, path))?;
+            Some(moved.remove(index).2)
+        })
+        .collect()
+}
+
+/// Whether `candidate` has `original`'s name, or that name with the number or time the
+/// Finder adds to avoid a clash, as in `Synth 2.vst3`.
+#[cfg(any(target_os = "macos", all(test, unix)))]
+fn is_binned_name(original: &Path, candidate: &Path) -> bool {
+    if original.file_name() == candidate.file_name() {
+        return true;
+    }
+    let (Some(stem), Some(candidate_stem)) = (original.file_stem(), candidate.file_stem()) else {
+        return false;
+    };
+    original.extension() == candidate.extension()
+        && candidate_stem
+            .to_string_lossy()
+            .starts_with(&format!("{} ", stem.to_string_lossy()))
+}
+
+/// Whether a path belongs to the operating system rather than to a plugin vendor.
+pub fn is_system_path(path: &Path) -> bool {
+    system_roots().iter().any(|root| path.starts_with(root))
+}
+
+#[cfg(target_os = "macos")]
+fn system_roots() -> Vec<PathBuf> {
+    ["/System", "/usr", "/bin", "/sbin", "/Library/Apple"]
+        .iter()
+        .map(PathBuf::from)
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn system_roots() -> Vec<PathBuf> {
+    std::env::var_os("SystemRoot")
+        .map(PathBuf::from)
+        .into_iter()
+        .collect()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn system_roots() -> Vec<PathBuf> {
+    ["/usr", "/bin", "/sbin", "/lib", "/etc"]
+        .iter()
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Whether removing `path` needs administrator rights: this user can't write to its
+/// folder through the owner, group or everyone permissions, or the folder is sticky
+/// and neither it nor the item belongs to this user.
+#[cfg(unix)]
+pub fn needs_admin(path: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::MetadataExt;
+
+    let Some(parent) = path.parent() else {
+        return false;
+    };
+    let Ok(parent_c) = std::ffi::CString::new(parent.as_os_str().as_bytes()) else {
+        return false;
+    };
+    // access() checks the real user and every group it belongs to.
+    if unsafe { libc::access(parent_c.as_ptr(), libc::W_OK) } != 0 {
+        return std::io::Error::last_os_error().raw_os_error() == Some(libc::EACCES);
+    }
+
+    let uid = unsafe { libc::getuid() };
+    let owned_by_user = |path: &Path| std::fs::symlink_metadata(path).is_ok_and(|m| m.uid() == uid);
+    let sticky = std::fs::metadata(parent).is_ok_and(|m| m.mode() & 0o1000 != 0);
+    sticky && uid != 0 && !owned_by_user(parent) && !owned_by_user(path)
+}
+
+/// Program Files, Common Files and the Windows folder are only writable by
+/// administrators unless an installer loosened their permissions.
+#[cfg(target_os = "windows")]
+pub fn needs_admin(path: &Path) -> bool {
+    [
+        "ProgramFiles",
+        "ProgramFiles(x86)",
+        "CommonProgramFiles",
+        "CommonProgramFiles(x86)",
+        "SystemRoot",
+    ]
+    .iter()
+    .filter_map(std::env::var_os)
+    .any(|root| path.starts_with(root))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn writable_folders_need_no_admin() {
+        let dir = tempfile::tempdir().unwrap();
+        let group_writable = dir.path().join("Shared");
+        std::fs::create_dir(&group_writable).unwrap();
+        std::fs::set_permissions(&group_writable, std::fs::Permissions::from_mode(0o775)).unwrap();
+
+        assert!(!needs_admin(&dir.path().join("Synth.vst3")));
+        assert!(!needs_admin(&group_writable.join("Synth.vst3")));
+    }
+
+    #[test]
+    fn read_only_folders_need_admin() {
+        // Root can write anywhere, so there's nothing to check.
+        if unsafe { libc::getuid() } == 0 {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let locked = dir.path().join("Locked");
+        std::fs::create_dir(&locked).unwrap();
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o555)).unwrap();
+
+        assert!(needs_admin(&locked.join("Synth.vst3")));
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn finds_each_item_in_the_bin() {
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path();
+        for name in [
+            "Synth.vst3",
+            "Synth 2.vst3",
+            "Synthesizer.vst3",
+            "Synth 3.clap",
+        ] {
+            std::fs::create_dir(bin.join(name)).unwrap();
+        }
+        let since = SystemTime::now() - std::time::Duration::from_secs(5);
+
+        let originals = [
+            PathBuf::from("/Library/VST3/Synth.vst3"),
+            PathBuf::from("/Users/me/VST3/Synth.vst3"),
+            PathBuf::from("/Library/VST3/Synth.vst3"),
+        ];
+        let found = locate_in(bin, &originals, since);
+        let mut names: Vec<_> = found[..2]
+            .iter()
+            .map(|p| p.as_ref().unwrap().file_name().unwrap().to_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["Synth 2.vst3", "Synth.vst3"]);
+        assert_eq!(found[2], None);
+    }
+
+    #[test]
+    fn ignores_items_binned_before_the_operation() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("Synth.vst3")).unwrap();
+        let later = SystemTime::now() + std::time::Duration::from_secs(60);
+
+        let originals = [PathBuf::from("/Library/VST3/Synth.vst3")];
+        assert_eq!(locate_in(dir.path(), &originals, later), [None]);
+    }
+}
@@ -1 +1,4 @@
+pub mod config;
+pub mod elevation;
 pub mod error;
+pub mod fs;